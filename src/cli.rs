@@ -0,0 +1,303 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use clap::{Parser, Subcommand};
+
+use crate::collection::{self, Defaults};
+use crate::environment;
+use crate::history::History;
+use crate::report::{Case, Reporter};
+use crate::request::body::BodyKind;
+use crate::request::http::Http;
+use crate::request::response::format_duration;
+use crate::request::{assertion, extraction, oauth2};
+use crate::request::{Request, RequestContext};
+use crate::Weaver;
+
+/// Weaver is a simple,easy-to-use and cross-platform API tool.
+/// Launch the window if no subcommand is given.
+#[derive(Parser)]
+#[clap(name = "weaver", version, about)]
+pub struct Cli {
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Run saved requests without window
+    Run {
+        /// Name,path like `collection/folder/name` or id of saved requests
+        requests: Vec<String>,
+        /// Run all saved requests
+        #[clap(long)]
+        all: bool,
+        /// Name of environment used to resolve `{{var}}`,the active one of window is used by default
+        #[clap(long)]
+        env: Option<String>,
+        /// Don't print response body
+        #[clap(long)]
+        no_body: bool,
+        /// Write a report of results,it's printed instead of progress if `--out` isn't given
+        #[clap(long, value_enum)]
+        reporter: Option<Reporter>,
+        /// File which report is written to
+        #[clap(long, requires = "reporter")]
+        out: Option<PathBuf>,
+    },
+    /// List saved requests with their path
+    List,
+}
+
+impl Command {
+    /// Execute command and return the exit code of process.
+    pub fn execute(self) -> i32 {
+        let weaver = match load_weaver() {
+            Ok(weaver) => weaver,
+            Err(err) => {
+                eprintln!("{}", err);
+                return 2;
+            }
+        };
+        match self {
+            Command::Run {
+                requests,
+                all,
+                env,
+                no_body,
+                reporter,
+                out,
+            } => run(&weaver, requests, all, env, no_body, reporter, out),
+            Command::List => {
+                for (path, http, _) in weaver.collections.requests() {
+                    println!("{}\t{}\t{}", http.get_id(), http.method_name(), path);
+                }
+                0
+            }
+        }
+    }
+}
+
+/// Print progress,to stderr if report is printed to stdout.
+macro_rules! log {
+    ($stderr:expr) => {
+        log!($stderr, "")
+    };
+    ($stderr:expr, $($arg:tt)*) => {
+        match $stderr {
+            true => eprintln!($($arg)*),
+            false => println!($($arg)*),
+        }
+    };
+}
+
+fn run(
+    weaver: &Weaver,
+    names: Vec<String>,
+    all: bool,
+    env: Option<String>,
+    no_body: bool,
+    reporter: Option<Reporter>,
+    out: Option<PathBuf>,
+) -> i32 {
+    let stderr = reporter.is_some() && out.is_none();
+    let environment = match env {
+        None => weaver.environments.active(),
+        Some(name) => match weaver.environments.find(&name) {
+            None => {
+                eprintln!("No such environment: {}", name);
+                return 2;
+            }
+            Some(environment) => Some(environment),
+        },
+    };
+    let mut variables = environment.map(|e| e.variables()).unwrap_or_default();
+    let session = environment.map(|e| e.name.as_str()).unwrap_or_default();
+    // History isn't saved in command line.
+    let mut history = History::default();
+
+    let saved = weaver.collections.requests();
+    let requests: Vec<&(String, &Http, Defaults)> = if all {
+        saved.iter().collect()
+    } else {
+        if names.is_empty() {
+            eprintln!("Give names or ids of requests,or use `--all` to run all of them");
+            return 2;
+        }
+        let mut requests = vec![];
+        for name in &names {
+            match saved.iter().find(|(path, http, _)| {
+                http.get_id() == *name || path == name || http.request_name() == name
+            }) {
+                None => {
+                    eprintln!("No such request: {}", name);
+                    return 2;
+                }
+                Some(request) => requests.push(request),
+            }
+        }
+        requests
+    };
+
+    let mut cases = vec![];
+    let started = Instant::now();
+    let total = requests.len();
+    for (index, (path, http, defaults)) in requests.into_iter().enumerate() {
+        log!(
+            stderr,
+            "[{}/{}] {} {} {}",
+            index + 1,
+            total,
+            path,
+            http.method_name(),
+            collection::join_url(&defaults.base_url, http.url())
+        );
+        let mut updates = Default::default();
+        let context = RequestContext {
+            variables: &variables,
+            defaults,
+            history: &mut history,
+            settings: &weaver.settings,
+            session,
+            updates: &mut updates,
+        };
+        let sent = Instant::now();
+        let mut case = Case {
+            path: path.clone(),
+            method: http.method_name(),
+            url: environment::substitute(
+                &collection::join_url(&defaults.base_url, http.url()),
+                &variables,
+            ),
+            status: None,
+            elapsed: Duration::ZERO,
+            error: None,
+            outcomes: vec![],
+        };
+        let sending = http.send(&context);
+        // There is no auth panel to show url of OAuth2 authorization.
+        let mut printed = vec![];
+        while sending.ready().is_none() {
+            for authorizing in oauth2::authorizing_urls() {
+                if !printed.contains(&authorizing.url) {
+                    log!(
+                        stderr,
+                        "Open this url in browser to authorize: {}",
+                        authorizing.url
+                    );
+                    printed.push(authorizing.url);
+                }
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        match sending.block_until_ready() {
+            Ok(response) => {
+                case.url = response.url.clone();
+                case.status = Some(response.code.as_u16());
+                case.elapsed = response.elapsed;
+                log!(
+                    stderr,
+                    "{} {} ,Time: {} ,Size: {}",
+                    response.code.as_str(),
+                    response.code.canonical_reason().unwrap_or(""),
+                    format_duration(response.elapsed),
+                    response.size.unwrap_or(response.bytes.len() as u64)
+                );
+                if !no_body {
+                    match response.kind {
+                        BodyKind::Text => log!(stderr, "{}", response.body),
+                        _ => log!(stderr, "<{} bytes of binary body>", response.bytes.len()),
+                    }
+                }
+                let outcomes = assertion::evaluate(http.assertions(), response, &variables);
+                for outcome in &outcomes {
+                    match &outcome.error {
+                        None => log!(stderr, "  ✔ {}", outcome.name),
+                        Some(err) => log!(stderr, "  ✘ {}: {}", outcome.name, err),
+                    }
+                }
+                // Extracted values are used by later requests of this run.
+                for extracted in extraction::extract(http.extractions(), response, &variables) {
+                    match extracted.value {
+                        Ok(value) => {
+                            log!(
+                                stderr,
+                                "  {} = {} (from {})",
+                                extracted.variable,
+                                value,
+                                extracted.source
+                            );
+                            variables.insert(extracted.variable, value);
+                        }
+                        Err(err) => log!(
+                            stderr,
+                            "  {} (from {}): {}",
+                            extracted.variable,
+                            extracted.source,
+                            err
+                        ),
+                    }
+                }
+                case.outcomes = outcomes;
+            }
+            Err(err) => {
+                log!(stderr, "Error: {}", err);
+                case.elapsed = sent.elapsed();
+                case.error = Some(err.to_string());
+            }
+        }
+        cases.push(case);
+        log!(stderr);
+    }
+    // Request with error or failed assertions is failed.
+    let failed = cases.iter().filter(|case| !case.passed()).count();
+    log!(stderr, "{} requests,{} failed", total, failed);
+    if let Some(reporter) = reporter {
+        let report = reporter.report(&cases, started.elapsed());
+        match &out {
+            None => print!("{}", report),
+            Some(out) => {
+                if let Err(err) = std::fs::write(out, report) {
+                    eprintln!("Failed to write report to {}: {}", out.display(), err);
+                    return 2;
+                }
+            }
+        }
+    }
+    if failed > 0 {
+        1
+    } else {
+        0
+    }
+}
+
+/// Path of state saved by `eframe`,see `eframe::native::file_storage::FileStorage::from_app_name`.
+fn storage_path() -> Option<PathBuf> {
+    directories_next::ProjectDirs::from("", "", "weaver")
+        .map(|dirs| dirs.data_dir().join("app.ron"))
+}
+
+/// Load the same state of window.
+fn load_weaver() -> Result<Weaver, String> {
+    let path = match storage_path() {
+        None => return Err("Failed to find path of saved state".to_string()),
+        Some(path) => path,
+    };
+    let file = match std::fs::File::open(&path) {
+        Ok(file) => file,
+        Err(err) => return Err(format!("Failed to open {}: {}", path.display(), err)),
+    };
+    let kv: HashMap<String, String> = match ron::de::from_reader(file) {
+        Ok(kv) => kv,
+        Err(err) => return Err(format!("Failed to read {}: {}", path.display(), err)),
+    };
+    match kv.get(eframe::APP_KEY) {
+        None => Ok(Weaver::default()),
+        Some(value) => {
+            let mut weaver: Weaver = ron::from_str(value).map_err(|err| err.to_string())?;
+            weaver.migrate();
+            Ok(weaver)
+        }
+    }
+}
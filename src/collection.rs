@@ -0,0 +1,809 @@
+use std::ops::Add;
+
+use eframe::egui::collapsing_header::paint_default_icon;
+use eframe::egui::style::Margin;
+use eframe::egui::{
+    Context, CursorIcon, Grid, Id, Label, Rect, RichText, Rounding, ScrollArea, Sense, Stroke,
+    TextEdit, Ui, Vec2, Window,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::components::Frame;
+use crate::environment::Variables;
+use crate::request::graphql::GraphQL;
+use crate::request::http::{get_uuid, Http};
+use crate::request::websocket::WebSocket;
+use crate::request::{ClickType, Request};
+use crate::{color, components, WeaverStyle};
+
+/// Indent of each level in request tree.
+const INDENT: f32 = 12.0;
+
+/// Item of request tree in sidebar,requests of different kinds are kept side by side.
+#[derive(Deserialize, Serialize)]
+pub enum Node {
+    Folder(Folder),
+    Http(Box<Http>),
+    WebSocket(Box<WebSocket>),
+    GraphQL(Box<GraphQL>),
+}
+
+/// Folder of requests,a folder at top level is a collection.
+#[derive(Deserialize, Serialize)]
+pub struct Folder {
+    id: String,
+    pub name: String,
+    /// Prefix of child request urls which are not absolute.
+    pub base_url: String,
+    /// Headers added to child requests which don't set the same header.
+    pub header: Vec<(String, String)>,
+    pub children: Vec<Node>,
+    collapsed: bool,
+}
+
+impl Default for Folder {
+    fn default() -> Self {
+        Self {
+            id: get_uuid(),
+            name: "New folder".to_string(),
+            base_url: "".to_string(),
+            header: vec![],
+            children: vec![],
+            collapsed: false,
+        }
+    }
+}
+
+/// Settings inherited from parent folders,inner folders override outer ones.
+#[derive(Clone, Default, Debug, PartialEq, Eq)]
+pub struct Defaults {
+    pub base_url: String,
+    pub header: Vec<(String, String)>,
+}
+
+impl Defaults {
+    fn inherit(&self, folder: &Folder) -> Self {
+        let mut header: Vec<(String, String)> = self
+            .header
+            .iter()
+            .filter(|(key, _)| {
+                !folder
+                    .header
+                    .iter()
+                    .any(|(k, _)| k.trim().eq_ignore_ascii_case(key))
+            })
+            .cloned()
+            .collect();
+        header.extend(
+            folder
+                .header
+                .iter()
+                .filter(|(key, _)| !key.trim().is_empty())
+                .map(|(key, value)| (key.trim().to_string(), value.clone())),
+        );
+        Self {
+            base_url: match folder.base_url.trim() {
+                "" => self.base_url.clone(),
+                base_url => base_url.to_string(),
+            },
+            header,
+        }
+    }
+
+    /// Headers of request with inherited headers which are not overridden.
+    pub fn merge_header(&self, header: &[(String, String)]) -> Vec<(String, String)> {
+        let mut result: Vec<(String, String)> = self
+            .header
+            .iter()
+            .filter(|(key, _)| !header.iter().any(|(k, _)| k.eq_ignore_ascii_case(key)))
+            .cloned()
+            .collect();
+        result.extend(header.iter().cloned());
+        result
+    }
+}
+
+/// Join base url and url of request,an url which contains scheme is kept as it is.
+pub fn join_url(base_url: &str, url: &str) -> String {
+    if base_url.is_empty() || url.contains("://") {
+        return url.to_string();
+    }
+    if url.is_empty() {
+        return base_url.to_string();
+    }
+    format!(
+        "{}/{}",
+        base_url.trim_end_matches('/'),
+        url.trim_start_matches('/')
+    )
+}
+
+impl Node {
+    pub fn id(&self) -> String {
+        match self {
+            Node::Folder(folder) => folder.id.clone(),
+            Node::Http(http) => http.get_id(),
+            Node::WebSocket(websocket) => websocket.get_id(),
+            Node::GraphQL(graphql) => graphql.get_id(),
+        }
+    }
+
+    /// `None` if it's a folder.
+    fn request(&self) -> Option<&dyn Request> {
+        match self {
+            Node::Folder(_) => None,
+            Node::Http(http) => Some(http.as_ref()),
+            Node::WebSocket(websocket) => Some(websocket.as_ref()),
+            Node::GraphQL(graphql) => Some(graphql.as_ref()),
+        }
+    }
+
+    fn request_mut(&mut self) -> Option<&mut dyn Request> {
+        match self {
+            Node::Folder(_) => None,
+            Node::Http(http) => Some(http.as_mut()),
+            Node::WebSocket(websocket) => Some(websocket.as_mut()),
+            Node::GraphQL(graphql) => Some(graphql.as_mut()),
+        }
+    }
+
+    fn contains(&self, id: &str) -> bool {
+        match self {
+            Node::Folder(folder) => folder
+                .children
+                .iter()
+                .any(|node| node.id() == id || node.contains(id)),
+            _ => false,
+        }
+    }
+}
+
+/// Where a dragged node is dropped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Target {
+    /// Before the node with this id,in the same folder.
+    Before(String),
+    /// At the end of folder with this id.
+    Into(String),
+    /// At the end of top level.
+    Root,
+}
+
+enum Action {
+    Select(String),
+    Delete(String),
+    Move(String, Target),
+    Toggle(String),
+    Drag(String),
+    /// Add request to folder with this id,or top level.
+    Add(Node, Option<String>),
+    NewFolder(Option<String>),
+    Edit(String),
+    Run(String),
+}
+
+/// Tree of collections,folders and requests which is shown in sidebar.
+#[derive(Deserialize, Serialize, Default)]
+pub struct Collections {
+    nodes: Vec<Node>,
+    /// Id of active request.
+    active: Option<String>,
+    #[serde(skip)]
+    dragging: Option<String>,
+    /// Id of folder which is editing in folder window.
+    #[serde(skip)]
+    editing: Option<String>,
+    /// Id of folder which is asked to run in runner.
+    #[serde(skip)]
+    pub run: Option<String>,
+}
+
+impl Collections {
+    /// Requests saved by older version are a flat list,move them to top level.
+    pub fn migrate(&mut self, requests: Vec<Http>) {
+        if self.active.is_none() {
+            self.active = requests.first().map(|http| http.get_id());
+        }
+        self.nodes
+            .extend(requests.into_iter().map(|http| Node::Http(Box::new(http))));
+    }
+
+    /// Add node to the beginning of folder,or top level if `parent` is `None`.
+    pub fn add(&mut self, node: Node, parent: Option<&str>) {
+        if let Some(request) = node.request() {
+            self.active = Some(request.get_id());
+        }
+        match parent.and_then(|id| folder_mut(&mut self.nodes, id)) {
+            None => self.nodes.insert(0, node),
+            Some(folder) => {
+                folder.collapsed = false;
+                folder.children.insert(0, node);
+            }
+        }
+    }
+
+    /// Add a folder and open folder window to edit it,folder at top level is a collection.
+    pub fn add_folder(&mut self, parent: Option<&str>) {
+        let folder = Folder {
+            name: match parent {
+                None => "New collection".to_string(),
+                Some(_) => "New folder".to_string(),
+            },
+            ..Folder::default()
+        };
+        self.editing = Some(folder.id.clone());
+        self.add(Node::Folder(folder), parent);
+    }
+
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.active = None;
+    }
+
+    /// Active request and settings inherited from its folders.
+    pub fn active_mut(&mut self) -> Option<(&mut dyn Request, Defaults)> {
+        let id = self.active.clone()?;
+        find_request_mut(&mut self.nodes, &id, Defaults::default())
+    }
+
+    /// All http requests with their path and inherited settings,in order of tree.
+    pub fn requests(&self) -> Vec<(String, &Http, Defaults)> {
+        let mut result = vec![];
+        collect_requests(&self.nodes, "", &Defaults::default(), &mut result);
+        result
+    }
+
+    /// All folders with their id,path and ids of http requests inside,in order of tree.
+    pub fn folders(&self) -> Vec<(String, String, Vec<String>)> {
+        let mut result = vec![];
+        collect_folders(&self.nodes, "", &mut result);
+        result
+    }
+
+    pub fn sidebar(&mut self, ui: &mut Ui, style: &WeaverStyle, variables: &Variables) {
+        let mut actions = vec![];
+        let released = ui.input().pointer.any_released();
+        let mut state = TreeState {
+            active: self.active.as_deref(),
+            dragging: self.dragging.as_deref(),
+            released,
+            actions: &mut actions,
+            variables,
+        };
+        nodes_view(&self.nodes, 0, &Defaults::default(), ui, style, &mut state);
+
+        if let Some(dragging) = &self.dragging {
+            ui.output().cursor_icon = CursorIcon::Grabbing;
+            ui.add_space(5.0);
+            let response = Frame {
+                inner_margin: Margin::same(10.0),
+                stroke: Stroke::new(1.0, color::GRAY),
+                ..Frame::default()
+            }
+            .show(ui, |ui| {
+                ui.set_width(ui.available_width());
+                ui.label("Move to top level");
+            })
+            .response;
+            if released && ui.rect_contains_pointer(response.rect) {
+                actions.push(Action::Move(dragging.clone(), Target::Root));
+            }
+        }
+        if released {
+            self.dragging = None;
+        }
+
+        for action in actions {
+            match action {
+                Action::Select(id) => self.active = Some(id),
+                Action::Delete(id) => {
+                    if let Some(node) = take(&mut self.nodes, &id) {
+                        if let Some(active) = &self.active {
+                            if node.id() == *active || node.contains(active) {
+                                self.active = None;
+                            }
+                        }
+                    }
+                }
+                Action::Move(id, target) => self.move_node(&id, target),
+                Action::Toggle(id) => {
+                    if let Some(folder) = folder_mut(&mut self.nodes, &id) {
+                        folder.collapsed = !folder.collapsed;
+                    }
+                }
+                Action::Add(node, parent) => self.add(node, parent.as_deref()),
+                Action::NewFolder(parent) => self.add_folder(parent.as_deref()),
+                Action::Drag(id) => self.dragging = Some(id),
+                Action::Edit(id) => self.editing = Some(id),
+                Action::Run(id) => self.run = Some(id),
+            }
+        }
+    }
+
+    fn move_node(&mut self, id: &str, target: Target) {
+        if let Target::Before(target_id) | Target::Into(target_id) = &target {
+            let is_inside = find(&self.nodes, id)
+                .map(|node| node.id() == *target_id || node.contains(target_id))
+                .unwrap_or(true);
+            if is_inside {
+                return;
+            }
+        }
+        let node = match take(&mut self.nodes, id) {
+            None => return,
+            Some(node) => node,
+        };
+        if let Some(node) = insert(&mut self.nodes, node, &target) {
+            self.nodes.push(node);
+        }
+    }
+
+    pub fn draw_folder_window(&mut self, ctx: &Context) {
+        let folder = match self
+            .editing
+            .clone()
+            .and_then(|id| folder_mut(&mut self.nodes, &id))
+        {
+            None => return,
+            Some(folder) => folder,
+        };
+        let mut show = true;
+        Window::new("Folder")
+            .resizable(true)
+            .open(&mut show)
+            .collapsible(false)
+            .show(ctx, |ui| folder.edit_view(ui));
+        if !show {
+            self.editing = None;
+        }
+    }
+}
+
+impl Folder {
+    pub fn new(name: &str, children: Vec<Node>) -> Self {
+        Self {
+            name: name.to_string(),
+            children,
+            ..Folder::default()
+        }
+    }
+
+    fn edit_view(&mut self, ui: &mut Ui) {
+        let (_, row_height) = crate::style::get_row_height(ui);
+        Grid::new("folder_grid").num_columns(2).show(ui, |ui| {
+            ui.label("Name");
+            ui.add(TextEdit::singleline(&mut self.name).desired_width(300.0));
+            ui.end_row();
+            ui.label("Base URL");
+            ui.add(
+                TextEdit::singleline(&mut self.base_url)
+                    .hint_text("https://example.com/api")
+                    .desired_width(300.0),
+            );
+            ui.end_row();
+        });
+        ui.add_space(5.0);
+        ui.label("Default headers");
+        ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+            let col_width = (ui.available_width() - row_height * 2.0) / 2.0;
+            let mut remove = None;
+            Grid::new("folder_header_grid")
+                .num_columns(3)
+                .show(ui, |ui| {
+                    for (index, (key, value)) in self.header.iter_mut().enumerate() {
+                        ui.add(TextEdit::singleline(key).desired_width(col_width));
+                        ui.add(TextEdit::singleline(value).desired_width(col_width));
+                        let next_pos = ui.next_widget_position();
+                        let clear_btn_rect = Rect::from_min_max(
+                            next_pos,
+                            next_pos.add(Vec2::splat(row_height / 1.5)),
+                        );
+                        ui.add_space(row_height);
+                        if components::close_button(
+                            ui,
+                            clear_btn_rect,
+                            Id::new(index.to_string() + "remove_folder_header_btn"),
+                        )
+                        .clicked()
+                        {
+                            remove = Some(index);
+                        }
+                        ui.end_row();
+                    }
+                });
+            if let Some(index) = remove {
+                self.header.remove(index);
+            }
+            ui.add_space(5.0);
+            ui.vertical_centered(|ui| {
+                ui.style_mut().visuals.widgets.hovered.expansion = 2.0;
+                let next_pos = ui.next_widget_position();
+                let add_btn_rect =
+                    Rect::from_min_max(next_pos, next_pos.add(Vec2::splat(row_height / 1.5)));
+                if components::add_button(ui, add_btn_rect, Id::new("add_folder_header_button"))
+                    .clicked()
+                {
+                    self.header.push(("".to_string(), "".to_string()));
+                }
+            });
+        });
+    }
+}
+
+struct TreeState<'a> {
+    active: Option<&'a str>,
+    dragging: Option<&'a str>,
+    /// Pointer is released in this frame,dragged node is dropped on hovered row.
+    released: bool,
+    actions: &'a mut Vec<Action>,
+    /// Variables of active environment.
+    variables: &'a Variables,
+}
+
+fn nodes_view(
+    nodes: &[Node],
+    depth: usize,
+    defaults: &Defaults,
+    ui: &mut Ui,
+    style: &WeaverStyle,
+    state: &mut TreeState,
+) {
+    for node in nodes {
+        let id = node.id();
+        let row = ui.horizontal(|ui| {
+            ui.add_space(depth as f32 * INDENT);
+            ui.vertical(|ui| match (node, node.request()) {
+                (Node::Folder(folder), _) => folder_row_view(folder, ui, state),
+                (_, None) => {}
+                (_, Some(request)) => {
+                    let is_active = state.active == Some(id.as_str());
+                    match request.request_name_view(is_active, ui, style, defaults, state.variables)
+                    {
+                        ClickType::Click => state.actions.push(Action::Select(id.clone())),
+                        ClickType::Delete => state.actions.push(Action::Delete(id.clone())),
+                        ClickType::Drag => state.actions.push(Action::Drag(id.clone())),
+                        ClickType::None => {}
+                    }
+                }
+            })
+        });
+        let rect = row.response.rect;
+
+        if let Some(dragging) = state.dragging {
+            if dragging != id && ui.rect_contains_pointer(rect) {
+                // Drop into folder,or before request.
+                let target = match node {
+                    Node::Folder(_) => Target::Into(id.clone()),
+                    _ => Target::Before(id.clone()),
+                };
+                let painter = ui.painter_at(rect.expand(2.0));
+                match target {
+                    Target::Into(_) => painter.rect_stroke(
+                        rect,
+                        Rounding::none(),
+                        Stroke::new(2.0, color::DODER_BLUE),
+                    ),
+                    _ => painter.line_segment(
+                        [rect.left_top(), rect.right_top()],
+                        Stroke::new(2.0, color::DODER_BLUE),
+                    ),
+                }
+                if state.released {
+                    state
+                        .actions
+                        .push(Action::Move(dragging.to_string(), target));
+                }
+            }
+        }
+
+        if let Node::Folder(folder) = node {
+            if !folder.collapsed {
+                let defaults = defaults.inherit(folder);
+                nodes_view(&folder.children, depth + 1, &defaults, ui, style, state);
+            }
+        }
+    }
+}
+
+fn folder_row_view(folder: &Folder, ui: &mut Ui, state: &mut TreeState) {
+    ui.style_mut().spacing.item_spacing = Vec2::new(0., 0.);
+    let response = Frame {
+        inner_margin: Margin {
+            left: 5.0,
+            right: 5.0,
+            top: 10.0,
+            bottom: 10.0,
+        },
+        fill: color::WHITE,
+        sense: Sense::click_and_drag(),
+        ..Frame::default()
+    }
+    .show(ui, |ui| {
+        ui.set_width(ui.available_width());
+        ui.horizontal(|ui| {
+            let (_, row_height) = crate::style::get_row_height(ui);
+            let (_, icon) = ui.allocate_exact_size(Vec2::splat(row_height / 2.0), Sense::hover());
+            paint_default_icon(ui, if folder.collapsed { 0.0 } else { 1.0 }, &icon);
+            ui.add_space(8.0);
+            ui.add(Label::new(RichText::new(folder.name.as_str()).strong()).wrap(false));
+        });
+    })
+    .response;
+    let response = response.context_menu(|ui| {
+        if ui.button("New request").clicked() {
+            let node = Node::Http(Box::default());
+            state
+                .actions
+                .push(Action::Add(node, Some(folder.id.clone())));
+            ui.close_menu();
+        }
+        if ui.button("New WebSocket").clicked() {
+            let node = Node::WebSocket(Box::default());
+            state
+                .actions
+                .push(Action::Add(node, Some(folder.id.clone())));
+            ui.close_menu();
+        }
+        if ui.button("New GraphQL").clicked() {
+            let node = Node::GraphQL(Box::default());
+            state
+                .actions
+                .push(Action::Add(node, Some(folder.id.clone())));
+            ui.close_menu();
+        }
+        if ui.button("New folder").clicked() {
+            state
+                .actions
+                .push(Action::NewFolder(Some(folder.id.clone())));
+            ui.close_menu();
+        }
+        ui.separator();
+        if ui.button("Run").clicked() {
+            state.actions.push(Action::Run(folder.id.clone()));
+            ui.close_menu();
+        }
+        if ui.button("Rename / Settings").clicked() {
+            state.actions.push(Action::Edit(folder.id.clone()));
+            ui.close_menu();
+        }
+        if ui.button("Delete").clicked() {
+            state.actions.push(Action::Delete(folder.id.clone()));
+            ui.close_menu();
+        }
+    });
+    if response.clicked() {
+        state.actions.push(Action::Toggle(folder.id.clone()));
+    }
+    if response.double_clicked() {
+        state.actions.push(Action::Edit(folder.id.clone()));
+    }
+    if response.drag_started() {
+        state.actions.push(Action::Drag(folder.id.clone()));
+    }
+}
+
+fn find<'a>(nodes: &'a [Node], id: &str) -> Option<&'a Node> {
+    for node in nodes {
+        if node.id() == id {
+            return Some(node);
+        }
+        if let Node::Folder(folder) = node {
+            if let Some(node) = find(&folder.children, id) {
+                return Some(node);
+            }
+        }
+    }
+    None
+}
+
+fn folder_mut<'a>(nodes: &'a mut [Node], id: &str) -> Option<&'a mut Folder> {
+    for node in nodes {
+        if let Node::Folder(folder) = node {
+            if folder.id == id {
+                return Some(folder);
+            }
+            if let Some(folder) = folder_mut(&mut folder.children, id) {
+                return Some(folder);
+            }
+        }
+    }
+    None
+}
+
+fn find_request_mut<'a>(
+    nodes: &'a mut [Node],
+    id: &str,
+    defaults: Defaults,
+) -> Option<(&'a mut dyn Request, Defaults)> {
+    for node in nodes {
+        if let Node::Folder(folder) = node {
+            let defaults = defaults.inherit(folder);
+            if let Some(result) = find_request_mut(&mut folder.children, id, defaults) {
+                return Some(result);
+            }
+        } else if node.id() == id {
+            return node.request_mut().map(|request| (request, defaults));
+        }
+    }
+    None
+}
+
+fn collect_requests<'a>(
+    nodes: &'a [Node],
+    path: &str,
+    defaults: &Defaults,
+    result: &mut Vec<(String, &'a Http, Defaults)>,
+) {
+    for node in nodes {
+        match node {
+            Node::Http(http) => result.push((
+                format!("{}{}", path, http.request_name()),
+                http,
+                defaults.clone(),
+            )),
+            Node::Folder(folder) => collect_requests(
+                &folder.children,
+                &format!("{}{}/", path, folder.name),
+                &defaults.inherit(folder),
+                result,
+            ),
+
+            // Only http requests can be run from command line.
+            Node::WebSocket(_) | Node::GraphQL(_) => {}
+        }
+    }
+}
+
+fn collect_folders(nodes: &[Node], path: &str, result: &mut Vec<(String, String, Vec<String>)>) {
+    for node in nodes {
+        if let Node::Folder(folder) = node {
+            let path = format!("{}{}/", path, folder.name);
+            let mut requests = vec![];
+            collect_requests(&folder.children, "", &Defaults::default(), &mut requests);
+            result.push((
+                folder.id.clone(),
+                path.clone(),
+                requests.iter().map(|(_, http, _)| http.get_id()).collect(),
+            ));
+            collect_folders(&folder.children, &path, result);
+        }
+    }
+}
+
+/// Remove node with this id from tree.
+fn take(nodes: &mut Vec<Node>, id: &str) -> Option<Node> {
+    if let Some(index) = nodes.iter().position(|node| node.id() == id) {
+        return Some(nodes.remove(index));
+    }
+    for node in nodes {
+        if let Node::Folder(folder) = node {
+            if let Some(node) = take(&mut folder.children, id) {
+                return Some(node);
+            }
+        }
+    }
+    None
+}
+
+/// Insert node to target,give the node back if target is not found.
+fn insert(nodes: &mut Vec<Node>, node: Node, target: &Target) -> Option<Node> {
+    match target {
+        Target::Root => nodes.push(node),
+        Target::Into(id) => match folder_mut(nodes, id) {
+            None => return Some(node),
+            Some(folder) => folder.children.push(node),
+        },
+        Target::Before(id) => match nodes.iter().position(|node| node.id() == *id) {
+            Some(index) => nodes.insert(index, node),
+            None => {
+                let mut node = node;
+                for child in nodes.iter_mut() {
+                    if let Node::Folder(folder) = child {
+                        match insert(&mut folder.children, node, target) {
+                            None => return None,
+                            Some(back) => node = back,
+                        }
+                    }
+                }
+                return Some(node);
+            }
+        },
+    }
+    None
+}
+
+#[test]
+fn test_tree() {
+    let http = |name: &str| {
+        let mut http = Http::default();
+        http.set_name(name);
+        http
+    };
+    let api = Folder {
+        name: "api".to_string(),
+        base_url: "https://example.com/api/".to_string(),
+        header: vec![
+            ("Accept".to_string(), "application/json".to_string()),
+            ("X-Version".to_string(), "1".to_string()),
+        ],
+        children: vec![Node::Http(Box::new(http("list")))],
+        ..Folder::default()
+    };
+    let api_id = api.id.clone();
+    let v2 = Folder {
+        name: "v2".to_string(),
+        base_url: "https://example.com/api/v2".to_string(),
+        header: vec![("x-version".to_string(), "2".to_string())],
+        ..Folder::default()
+    };
+    let v2_id = v2.id.clone();
+    let mut collections = Collections::default();
+    collections.migrate(vec![http("login"), http("logout")]);
+    collections.add(Node::Folder(api), None);
+    collections.add(Node::Folder(v2), Some(&api_id));
+
+    let paths = |collections: &Collections| {
+        collections
+            .requests()
+            .iter()
+            .map(|(path, ..)| path.clone())
+            .collect::<Vec<String>>()
+    };
+    assert_eq!(paths(&collections), vec!["api/list", "login", "logout"]);
+
+    // Move request into nested folder.
+    let login = collections.requests()[1].1.get_id();
+    collections.move_node(&login, Target::Into(v2_id.clone()));
+    assert_eq!(
+        paths(&collections),
+        vec!["api/v2/login", "api/list", "logout"]
+    );
+    let (_, _, defaults) = &collections.requests()[0];
+    assert_eq!(defaults.base_url, "https://example.com/api/v2");
+    assert_eq!(
+        defaults.header,
+        vec![
+            ("Accept".to_string(), "application/json".to_string()),
+            ("x-version".to_string(), "2".to_string()),
+        ]
+    );
+
+    // Reorder,and folder can't be moved into itself.
+    let logout = collections.requests()[2].1.get_id();
+    collections.move_node(&logout, Target::Before(login.clone()));
+    assert_eq!(
+        paths(&collections),
+        vec!["api/v2/logout", "api/v2/login", "api/list"]
+    );
+    collections.move_node(&api_id, Target::Into(v2_id));
+    assert_eq!(
+        paths(&collections),
+        vec!["api/v2/logout", "api/v2/login", "api/list"]
+    );
+    collections.move_node(&login, Target::Root);
+    assert_eq!(
+        paths(&collections),
+        vec!["api/v2/logout", "api/list", "login"]
+    );
+
+    // Folders with requests inside,which are run by runner.
+    let folders: Vec<(String, usize)> = collections
+        .folders()
+        .into_iter()
+        .map(|(_, path, ids)| (path, ids.len()))
+        .collect();
+    assert_eq!(
+        folders,
+        vec![("api/".to_string(), 2), ("api/v2/".to_string(), 1)]
+    );
+
+    assert_eq!(
+        join_url("https://example.com/api/", "/users"),
+        "https://example.com/api/users"
+    );
+    assert_eq!(
+        join_url("https://example.com/api", "http://other.com"),
+        "http://other.com"
+    );
+    assert_eq!(join_url("", "/users"), "/users");
+}
@@ -16,5 +16,11 @@ pub const LIGHT_SKY_BLUE: Color32 = Color32::from_rgb(135, 206, 250);
 #[allow(dead_code)]
 pub const GRAY: Color32 = Color32::from_rgb(158, 158, 158);
 
+/// #2E8B57
+pub const SEA_GREEN: Color32 = Color32::from_rgb(46, 139, 87);
+
+/// #FF8C00
+pub const DARK_ORANGE: Color32 = Color32::from_rgb(255, 140, 0);
+
 /// #000000
 pub const BLACK: Color32 = Color32::from_rgb(0, 0, 0);
@@ -3,7 +3,8 @@ mod frame;
 pub use frame::Frame;
 
 use crate::egui::Response;
-use eframe::egui::{Id, Rect, Sense, Ui, Vec2, Widget};
+use eframe::egui::{Button, Id, Rect, Sense, Ui, Vec2, Widget};
+use std::path::PathBuf;
 
 pub fn widget_with_size(ui: &mut Ui, size: Vec2, widget: impl Widget) -> Response {
     let widget_rect = Rect::from_min_size(
@@ -13,6 +14,28 @@ pub fn widget_with_size(ui: &mut Ui, size: Vec2, widget: impl Widget) -> Respons
     ui.put(widget_rect, widget)
 }
 
+/// Button which picks a file,secondary click clears it.
+pub fn file_button(ui: &mut Ui, path: &mut Option<PathBuf>) -> Response {
+    let name = path
+        .as_ref()
+        .and_then(|path| path.file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "Open file…".to_string());
+    let mut response = ui.add(Button::new(name));
+    if let Some(path) = path {
+        response = response.on_hover_text(path.display().to_string());
+    }
+    if response.clicked() {
+        if let Some(picked) = rfd::FileDialog::new().pick_file() {
+            *path = Some(picked);
+        }
+    }
+    if response.secondary_clicked() {
+        *path = None;
+    }
+    response
+}
+
 pub fn close_button(ui: &mut Ui, rect: Rect, id: Id) -> Response {
     let response = ui.interact(rect, id, Sense::click());
     ui.expand_to_include_rect(response.rect);
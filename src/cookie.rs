@@ -0,0 +1,417 @@
+use std::collections::BTreeMap;
+use std::ops::Add;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use chrono::{Local, TimeZone};
+use eframe::egui::{
+    CollapsingHeader, ComboBox, Context, Grid, Id, Rect, ScrollArea, TextEdit, Vec2, Window,
+};
+use reqwest::header::HeaderValue;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+use crate::{color, components};
+
+/// Cookie jar of each session,key is name of environment.
+static JARS: OnceLock<Mutex<BTreeMap<String, Arc<Jar>>>> = OnceLock::new();
+
+/// Jar of session,which is created if it doesn't exist.
+pub fn jar(session: &str) -> Arc<Jar> {
+    let mut jars = JARS.get_or_init(Default::default).lock().unwrap();
+    jars.entry(session.to_string()).or_default().clone()
+}
+
+fn sessions() -> Vec<(String, Arc<Jar>)> {
+    let jars = JARS.get_or_init(Default::default).lock().unwrap();
+    jars.iter()
+        .map(|(session, jar)| (session.clone(), jar.clone()))
+        .collect()
+}
+
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct Cookie {
+    pub name: String,
+    pub value: String,
+    /// Lowercase domain without leading dot.
+    pub domain: String,
+    /// Only sent to `domain` itself,not its subdomains.
+    pub host_only: bool,
+    pub path: String,
+    /// Unix time,`None` for session cookie.
+    pub expires: Option<i64>,
+    pub secure: bool,
+    pub http_only: bool,
+}
+
+/// Cookies shared by clients of the same session.
+#[derive(Default)]
+pub struct Jar {
+    cookies: Mutex<Vec<Cookie>>,
+}
+
+/// Cookies window,and cookies saved with state.
+#[derive(Deserialize, Serialize, Default)]
+pub struct Cookies {
+    /// Keep cookies after restarting.
+    persist: bool,
+    /// Cookies of each session,only used for saving and loading.
+    saved: BTreeMap<String, Vec<Cookie>>,
+    #[serde(skip)]
+    pub show_cookies_window: bool,
+    /// Session shown in window.
+    #[serde(skip)]
+    session: String,
+    #[serde(skip)]
+    filter: String,
+    /// Domain,name and value of cookie to add.
+    #[serde(skip)]
+    new_cookie: (String, String, String),
+}
+
+impl Cookie {
+    /// Parse `Set-Cookie` header of response from url,`None` if it's invalid or for another domain.
+    fn parse(set_cookie: &str, url: &Url) -> Option<Self> {
+        let parsed = ::cookie::Cookie::parse(set_cookie).ok()?;
+        let host = url.host_str()?.to_lowercase();
+        let (domain, host_only) = match parsed.domain() {
+            Some(domain) if !domain.is_empty() => {
+                let domain = domain.trim_start_matches('.').to_lowercase();
+                if !domain_match(&host, &domain) {
+                    return None;
+                }
+                (domain, false)
+            }
+            _ => (host, true),
+        };
+        let path = match parsed.path() {
+            Some(path) if path.starts_with('/') => path.to_string(),
+            _ => default_path(url.path()),
+        };
+        let expires = match parsed.max_age() {
+            Some(max_age) => Some(Local::now().timestamp() + max_age.whole_seconds()),
+            None => parsed
+                .expires_datetime()
+                .map(|expires| expires.unix_timestamp()),
+        };
+        Some(Self {
+            name: parsed.name().to_string(),
+            value: parsed.value().to_string(),
+            domain,
+            host_only,
+            path,
+            expires,
+            secure: parsed.secure().unwrap_or(false),
+            http_only: parsed.http_only().unwrap_or(false),
+        })
+    }
+
+    fn is_expired(&self, now: i64) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+
+    fn matches(&self, url: &Url) -> bool {
+        let host = match url.host_str() {
+            None => return false,
+            Some(host) => host.to_lowercase(),
+        };
+        let domain_matched = if self.host_only {
+            host == self.domain
+        } else {
+            domain_match(&host, &self.domain)
+        };
+        let path = url.path();
+        let path_matched = path == self.path
+            || (path.starts_with(&self.path)
+                && (self.path.ends_with('/') || path[self.path.len()..].starts_with('/')));
+        domain_matched && path_matched && (!self.secure || url.scheme() == "https")
+    }
+
+    fn expires_text(&self) -> String {
+        match self.expires {
+            None => "Session".to_string(),
+            Some(expires) => match Local.timestamp_opt(expires, 0).single() {
+                None => "-".to_string(),
+                Some(time) => time.format("%Y-%m-%d %H:%M:%S").to_string(),
+            },
+        }
+    }
+}
+
+/// `host` is `domain` or its subdomain.
+fn domain_match(host: &str, domain: &str) -> bool {
+    host == domain
+        || (host.ends_with(domain)
+            && host[..host.len() - domain.len()].ends_with('.')
+            && host.parse::<std::net::IpAddr>().is_err())
+}
+
+/// Directory of request path,used when cookie doesn't have `Path`.
+fn default_path(path: &str) -> String {
+    match path.rfind('/') {
+        Some(index) if index > 0 => path[..index].to_string(),
+        _ => "/".to_string(),
+    }
+}
+
+impl Jar {
+    fn insert(&self, cookie: Cookie) {
+        let mut cookies = self.cookies.lock().unwrap();
+        cookies.retain(|item| {
+            !(item.name == cookie.name && item.domain == cookie.domain && item.path == cookie.path)
+        });
+        // Cookie which is already expired deletes the old one.
+        if !cookie.is_expired(Local::now().timestamp()) {
+            cookies.push(cookie);
+        }
+    }
+
+    pub fn list(&self) -> Vec<Cookie> {
+        self.cookies.lock().unwrap().clone()
+    }
+
+    fn set_all(&self, cookies: Vec<Cookie>) {
+        *self.cookies.lock().unwrap() = cookies;
+    }
+}
+
+impl reqwest::cookie::CookieStore for Jar {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        for header in cookie_headers {
+            if let Some(cookie) = header
+                .to_str()
+                .ok()
+                .and_then(|header| Cookie::parse(header, url))
+            {
+                self.insert(cookie);
+            }
+        }
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        let now = Local::now().timestamp();
+        let mut cookies = self.cookies.lock().unwrap();
+        cookies.retain(|cookie| !cookie.is_expired(now));
+        let mut matched: Vec<&Cookie> = cookies.iter().filter(|c| c.matches(url)).collect();
+        if matched.is_empty() {
+            return None;
+        }
+        // Cookies with longer paths are listed first.
+        matched.sort_by_key(|c| std::cmp::Reverse(c.path.len()));
+        let header = matched
+            .iter()
+            .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+            .collect::<Vec<String>>()
+            .join("; ");
+        HeaderValue::from_str(&header).ok()
+    }
+}
+
+impl Cookies {
+    /// Move saved cookies into jars,should be called after loading.
+    pub fn restore(&mut self) {
+        for (session, cookies) in std::mem::take(&mut self.saved) {
+            jar(&session).set_all(cookies);
+        }
+    }
+
+    /// Copy cookies of jars to save if they should be persisted.
+    pub fn save(&mut self) {
+        self.saved = match self.persist {
+            false => Default::default(),
+            true => sessions()
+                .into_iter()
+                .map(|(session, jar)| (session, jar.list()))
+                .filter(|(_, cookies)| !cookies.is_empty())
+                .collect(),
+        };
+    }
+
+    pub fn draw_cookies_window(&mut self, ctx: &Context, active_session: &str) {
+        let mut show = self.show_cookies_window;
+        if !show {
+            self.session = active_session.to_string();
+        }
+        Window::new("Cookies")
+            .resizable(true)
+            .open(&mut show)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                let session_name = |session: &str| match session {
+                    "" => "No environment".to_string(),
+                    session => session.to_string(),
+                };
+                ui.horizontal(|ui| {
+                    ComboBox::from_id_source("cookie_session_combo_box")
+                        .selected_text(session_name(&self.session))
+                        .show_ui(ui, |ui| {
+                            for (session, _) in sessions() {
+                                let text = session_name(&session);
+                                ui.selectable_value(&mut self.session, session, text);
+                            }
+                        });
+                    ui.checkbox(&mut self.persist, "Keep after restart");
+                    if ui.button("Clear").clicked() {
+                        jar(&self.session).set_all(vec![]);
+                    }
+                });
+                ui.add(
+                    TextEdit::singleline(&mut self.filter)
+                        .hint_text("Filter by domain")
+                        .desired_width(f32::INFINITY),
+                );
+                ui.separator();
+                let jar = jar(&self.session);
+                let mut cookies = jar.cookies.lock().unwrap();
+                let mut domains: Vec<String> = cookies
+                    .iter()
+                    .map(|cookie| cookie.domain.clone())
+                    .filter(|domain| domain.contains(self.filter.trim()))
+                    .collect();
+                domains.sort();
+                domains.dedup();
+                let (_, row_height) = crate::style::get_row_height(ui);
+                ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    if domains.is_empty() {
+                        ui.colored_label(color::GRAY, "No cookies");
+                    }
+                    for domain in domains {
+                        CollapsingHeader::new(&domain)
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                Grid::new(Id::new("cookie_grid").with(&domain))
+                                    .num_columns(5)
+                                    .striped(true)
+                                    .show(ui, |ui| {
+                                        let mut label = 0;
+                                        cookies.retain_mut(|cookie| {
+                                            if cookie.domain != domain {
+                                                return true;
+                                            }
+                                            label += 1;
+                                            ui.label(&cookie.name);
+                                            ui.add(
+                                                TextEdit::singleline(&mut cookie.value)
+                                                    .desired_width(200.0),
+                                            );
+                                            ui.add(
+                                                TextEdit::singleline(&mut cookie.path)
+                                                    .desired_width(80.0),
+                                            );
+                                            let mut flags = vec![cookie.expires_text()];
+                                            if cookie.secure {
+                                                flags.push("Secure".to_string());
+                                            }
+                                            if cookie.http_only {
+                                                flags.push("HttpOnly".to_string());
+                                            }
+                                            ui.colored_label(color::GRAY, flags.join(" "));
+                                            let next_pos = ui.next_widget_position();
+                                            let rect = Rect::from_min_max(
+                                                next_pos,
+                                                next_pos.add(Vec2::splat(row_height / 1.5)),
+                                            );
+                                            ui.add_space(row_height);
+                                            let deleted = components::close_button(
+                                                ui,
+                                                rect,
+                                                Id::new(domain.clone() + "remove_cookie_btn")
+                                                    .with(label),
+                                            )
+                                            .clicked();
+                                            ui.end_row();
+                                            !deleted
+                                        });
+                                    });
+                            });
+                    }
+                });
+                drop(cookies);
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let (domain, name, value) = &mut self.new_cookie;
+                    ui.add(
+                        TextEdit::singleline(domain)
+                            .hint_text("Domain")
+                            .desired_width(150.0),
+                    );
+                    ui.add(
+                        TextEdit::singleline(name)
+                            .hint_text("Name")
+                            .desired_width(100.0),
+                    );
+                    ui.add(
+                        TextEdit::singleline(value)
+                            .hint_text("Value")
+                            .desired_width(150.0),
+                    );
+                    if ui.button("Add").clicked()
+                        && !domain.trim().is_empty()
+                        && !name.trim().is_empty()
+                    {
+                        jar.insert(Cookie {
+                            name: name.trim().to_string(),
+                            value: value.clone(),
+                            domain: domain.trim().trim_start_matches('.').to_lowercase(),
+                            host_only: false,
+                            path: "/".to_string(),
+                            expires: None,
+                            secure: false,
+                            http_only: false,
+                        });
+                        self.new_cookie = Default::default();
+                    }
+                });
+            });
+        self.show_cookies_window = show;
+    }
+}
+
+#[test]
+fn test_jar() {
+    use reqwest::cookie::CookieStore;
+
+    let jar = Jar::default();
+    let set = |url: &str, headers: &[&str]| {
+        let headers: Vec<HeaderValue> = headers
+            .iter()
+            .map(|header| HeaderValue::from_str(header).unwrap())
+            .collect();
+        jar.set_cookies(&mut headers.iter(), &Url::parse(url).unwrap());
+    };
+    let get = |url: &str| {
+        jar.cookies(&Url::parse(url).unwrap())
+            .map(|header| header.to_str().unwrap().to_string())
+    };
+    set(
+        "http://api.example.com/auth/login",
+        &[
+            "session=abc; HttpOnly",
+            "theme=dark; Domain=.example.com; Path=/",
+            "token=t; Path=/auth; Secure",
+            "evil=1; Domain=other.com",
+        ],
+    );
+    assert_eq!(jar.list().len(), 3);
+    assert_eq!(
+        get("http://api.example.com/auth/me").unwrap(),
+        "session=abc; theme=dark"
+    );
+    assert_eq!(
+        get("https://api.example.com/auth/me").unwrap(),
+        "session=abc; token=t; theme=dark"
+    );
+    assert_eq!(get("http://www.example.com/").unwrap(), "theme=dark");
+    assert_eq!(
+        get("http://api.example.com/authx"),
+        Some("theme=dark".to_string())
+    );
+    assert_eq!(get("http://other.com/"), None);
+
+    set(
+        "http://api.example.com/",
+        &["theme=; Domain=example.com; Max-Age=0"],
+    );
+    assert_eq!(get("http://www.example.com/"), None);
+    assert_eq!(default_path("/a/b/c"), "/a/b");
+    assert_eq!(default_path("/a"), "/");
+}
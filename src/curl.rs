@@ -1,9 +1,13 @@
 use std::path::PathBuf;
 
 use eframe::egui::{Button, Context, ScrollArea, TextEdit, Window};
+use reqwest::Url;
 
 use crate::color;
+use crate::request::auth::Auth;
 use crate::request::http::{FormParamType, Http};
+use crate::request::redirect::Redirect;
+use crate::request::tls::{ClientIdentity, Tls};
 
 #[derive(Default)]
 pub struct Curl {
@@ -81,7 +85,7 @@ fn test() {
     assert!(parse_curl(str).is_ok());
 }
 
-fn parse_curl(curl: &str) -> Result<Http, String> {
+pub(crate) fn parse_curl(curl: &str) -> Result<Http, String> {
     if curl.is_empty() {
         return Err("Empty curl command".to_string());
     }
@@ -98,6 +102,15 @@ fn parse_curl(curl: &str) -> Result<Http, String> {
     let mut form_param = vec![];
     let mut method = String::from("GET");
     let mut param_type = "".to_string();
+    let mut user = None;
+    let mut digest = false;
+    let mut tls = Tls::default();
+    // Curl doesn't follow redirects without `-L`.
+    let mut redirect = Redirect {
+        follow: false,
+        ..Default::default()
+    };
+    let mut identity = ClientIdentity::default();
 
     let mut i = 0;
     while i < result.len() {
@@ -106,7 +119,8 @@ fn parse_curl(curl: &str) -> Result<Http, String> {
                 "-H" | "--header" => {
                     i += 1;
                     if let Some(header) = result.get(i) {
-                        let result = split_string(":", header.as_str());
+                        let (key, value) = split_string(":", header.as_str());
+                        let result = (key.trim().to_string(), value.trim().to_string());
                         if header.to_lowercase().contains("content-type") {
                             param_type = result.1.clone();
                         }
@@ -114,6 +128,13 @@ fn parse_curl(curl: &str) -> Result<Http, String> {
                         header_vec.push(result);
                     }
                 }
+                "--form-string" => {
+                    i += 1;
+                    if let Some(param) = result.get(i) {
+                        let string = split_string("=", param);
+                        form_param.push((string.0, string.1, None, FormParamType::Text));
+                    }
+                }
                 "-F" | "--form" => {
                     i += 1;
                     if let Some(param) = result.get(i) {
@@ -138,10 +159,69 @@ fn parse_curl(curl: &str) -> Result<Http, String> {
                         text_param = param.clone()
                     }
                 }
+                "-u" | "--user" => {
+                    i += 1;
+                    if let Some(value) = result.get(i) {
+                        user = Some(split_string(":", value));
+                    }
+                }
+                "--digest" => digest = true,
+                "--basic" => digest = false,
+                "-k" | "--insecure" => tls.insecure = true,
+                "-L" | "--location" => redirect.follow = true,
+                "--max-redirs" => {
+                    i += 1;
+                    if let Some(max) = result.get(i).and_then(|value| value.parse::<i64>().ok()) {
+                        // `-1` means no limit.
+                        redirect.max = u32::try_from(max).unwrap_or(100);
+                    }
+                }
+                "--cacert" => {
+                    i += 1;
+                    if let Some(value) = result.get(i) {
+                        tls.ca_files.push(PathBuf::from(value));
+                    }
+                }
+                "-E" | "--cert" => {
+                    i += 1;
+                    if let Some(value) = result.get(i) {
+                        // `file:password`,colon of Windows drive isn't a separator.
+                        let (cert, password) = match value.get(2..).and_then(|rest| rest.find(':'))
+                        {
+                            Some(index) => (&value[..index + 2], &value[index + 3..]),
+                            None => (value.as_str(), ""),
+                        };
+                        identity.cert = PathBuf::from(cert);
+                        identity.password = password.to_string();
+                    }
+                }
+                "--key" => {
+                    i += 1;
+                    if let Some(value) = result.get(i) {
+                        identity.key = Some(PathBuf::from(value));
+                    }
+                }
+                "--pass" => {
+                    i += 1;
+                    if let Some(value) = result.get(i) {
+                        identity.password = value.clone();
+                    }
+                }
+                // Type is told by extension of file.
+                "--cert-type" | "--key-type" => i += 1,
+                "-I" | "--head" => {
+                    method = String::from("HEAD");
+                }
                 "-X" | "--request" => {
                     i += 1;
                     if let Some(method_result) = result.get(i) {
-                        method = method_result.to_uppercase()
+                        method = method_result.clone()
+                    }
+                }
+                "--url" => {
+                    i += 1;
+                    if let Some(value) = result.get(i) {
+                        url = Some(value.to_string());
                     }
                 }
                 _ => {
@@ -158,14 +238,45 @@ fn parse_curl(curl: &str) -> Result<Http, String> {
         return Err("No url found".to_string());
     }
 
-    Http::from_curl(
-        url.unwrap(),
-        method,
-        header_vec,
-        text_param,
-        form_param,
-        param_type,
-    )
+    // `-F` make curl send `multipart/form-data` without `Content-Type` header.
+    if param_type.is_empty() && !form_param.is_empty() {
+        param_type = "multipart/form-data".to_string();
+    }
+
+    let auth = match user {
+        None => Auth::None,
+        Some((username, password)) if digest => Auth::Digest { username, password },
+        Some((username, password)) => Auth::Basic { username, password },
+    };
+
+    let url = url.unwrap();
+    if !identity.cert.as_os_str().is_empty() {
+        // Certificate of curl command is only presented to its host.
+        identity.host = Url::parse(&url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_default();
+        tls.identities.push(identity);
+    }
+
+    let mut http = Http::from_curl(
+        url, method, header_vec, text_param, form_param, param_type, auth,
+    )?;
+    http.set_tls(tls);
+    http.set_redirect(redirect);
+    Ok(http)
+}
+
+/// Quote string for POSIX shell,only quote it when necessary.
+pub fn quote(str: &str) -> String {
+    if !str.is_empty()
+        && str
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=@%+,".contains(c))
+    {
+        return str.to_string();
+    }
+    format!("'{}'", str.replace('\'', r#"'\''"#))
 }
 
 fn split_string(regex: &str, origin: &str) -> (String, String) {
@@ -178,6 +289,18 @@ fn split_string(regex: &str, origin: &str) -> (String, String) {
     }
 }
 
+#[test]
+fn test_quote() {
+    assert_eq!(quote("http://localhost/a"), "http://localhost/a");
+    assert_eq!(quote(""), "''");
+    assert_eq!(quote("a b"), "'a b'");
+    assert_eq!(quote("it's"), r#"'it'\''s'"#);
+    assert_eq!(
+        shellwords::split(&quote(r#"{"name":"it's $HOME"}"#)).unwrap(),
+        vec![r#"{"name":"it's $HOME"}"#.to_string()]
+    );
+}
+
 #[test]
 fn test_split_string() {
     let regex = ":";
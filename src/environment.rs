@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+use std::ops::Add;
+
+use eframe::egui::text::{LayoutJob, TextFormat};
+use eframe::egui::{
+    Context, FontId, FontSelection, Grid, Id, Rect, ScrollArea, TextEdit, Ui, Vec2, Window,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{color, components};
+
+/// Variables of environment,key is the name used in `{{name}}`.
+pub type Variables = HashMap<String, String>;
+
+/// Named environments,only one of them can be active at the same time.
+#[derive(Deserialize, Serialize, Default)]
+pub struct Environments {
+    list: Vec<Environment>,
+    active: Option<usize>,
+    #[serde(skip)]
+    pub show_environment_window: bool,
+    // Index of environment which is editing in environment window.
+    #[serde(skip)]
+    editing: usize,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct Environment {
+    pub name: String,
+    pub variables: Vec<(String, String)>,
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Self {
+            name: "New environment".to_string(),
+            variables: vec![],
+        }
+    }
+}
+
+impl Environment {
+    pub fn variables(&self) -> Variables {
+        self.variables
+            .iter()
+            .filter(|(key, _)| !key.trim().is_empty())
+            .map(|(key, value)| (key.trim().to_string(), value.clone()))
+            .collect()
+    }
+}
+
+impl Environments {
+    pub fn active(&self) -> Option<&Environment> {
+        self.active.and_then(|index| self.list.get(index))
+    }
+
+    pub fn find(&self, name: &str) -> Option<&Environment> {
+        self.list
+            .iter()
+            .find(|environment| environment.name == name)
+    }
+
+    /// Variables of active environment,empty if there is no active environment.
+    pub fn variables(&self) -> Variables {
+        match self.active() {
+            None => Default::default(),
+            Some(environment) => environment.variables(),
+        }
+    }
+
+    pub fn add(&mut self, environment: Environment) {
+        self.list.push(environment);
+    }
+
+    /// Insert or replace variables of active environment,nothing is kept without active environment.
+    pub fn update(&mut self, values: Variables) {
+        let Some(environment) = self.active.and_then(|index| self.list.get_mut(index)) else {
+            return;
+        };
+        for (key, value) in values {
+            match environment
+                .variables
+                .iter_mut()
+                .find(|(name, _)| name.trim() == key)
+            {
+                Some((_, old)) => *old = value,
+                None => environment.variables.push((key, value)),
+            }
+        }
+    }
+
+    /// Picker of active environment,used in menu bar.
+    pub fn select_menu(&mut self, ui: &mut Ui) {
+        ui.menu_button(
+            match self.active() {
+                None => "No environment".to_string(),
+                Some(environment) => format!("Env: {}", environment.name),
+            },
+            |ui| {
+                if ui
+                    .selectable_value(&mut self.active, None, "No environment")
+                    .clicked()
+                {
+                    ui.close_menu();
+                }
+                for (index, environment) in self.list.iter().enumerate() {
+                    if ui
+                        .selectable_value(&mut self.active, Some(index), environment.name.as_str())
+                        .clicked()
+                    {
+                        ui.close_menu();
+                    }
+                }
+                ui.separator();
+                if ui.button("Manage").clicked() {
+                    self.show_environment_window = true;
+                    ui.close_menu();
+                }
+            },
+        );
+    }
+
+    pub fn draw_environment_window(&mut self, ctx: &Context) {
+        let mut show = self.show_environment_window;
+        Window::new("Environments")
+            .resizable(true)
+            .open(&mut show)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("New").clicked() {
+                        self.list.push(Environment::default());
+                        self.editing = self.list.len() - 1;
+                    }
+                    if !self.list.is_empty() && ui.button("Delete").clicked() {
+                        self.remove(self.editing);
+                    }
+                });
+                ui.separator();
+                if self.list.is_empty() {
+                    ui.label("No environment");
+                    return;
+                }
+                ui.horizontal_top(|ui| {
+                    ui.vertical(|ui| {
+                        ui.set_width(150.0);
+                        for (index, environment) in self.list.iter().enumerate() {
+                            ui.selectable_value(
+                                &mut self.editing,
+                                index,
+                                environment.name.as_str(),
+                            );
+                        }
+                    });
+                    ui.separator();
+                    ui.vertical(|ui| {
+                        if let Some(environment) = self.list.get_mut(self.editing) {
+                            environment.edit_view(ui);
+                        }
+                    });
+                });
+            });
+        self.show_environment_window = show;
+    }
+
+    fn remove(&mut self, index: usize) {
+        if index >= self.list.len() {
+            return;
+        }
+        self.list.remove(index);
+        self.active = match self.active {
+            Some(active) if active == index => None,
+            Some(active) if active > index => Some(active - 1),
+            active => active,
+        };
+        self.editing = self.editing.saturating_sub(1);
+    }
+}
+
+impl Environment {
+    fn edit_view(&mut self, ui: &mut Ui) {
+        let (_, row_height) = crate::style::get_row_height(ui);
+        ui.add(TextEdit::singleline(&mut self.name).desired_width(f32::INFINITY));
+        ui.add_space(5.0);
+        ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+            let col_width = (ui.available_width() - row_height * 2.0) / 2.0;
+            let mut remove = None;
+            Grid::new("environment_variable_grid")
+                .num_columns(3)
+                .show(ui, |ui| {
+                    ui.label("Variable");
+                    ui.label("Value");
+                    ui.end_row();
+                    for (index, (key, value)) in self.variables.iter_mut().enumerate() {
+                        ui.add(TextEdit::singleline(key).desired_width(col_width));
+                        ui.add(TextEdit::singleline(value).desired_width(col_width));
+                        let next_pos = ui.next_widget_position();
+                        let clear_btn_rect = Rect::from_min_max(
+                            next_pos,
+                            next_pos.add(Vec2::splat(row_height / 1.5)),
+                        );
+                        ui.add_space(row_height);
+                        if components::close_button(
+                            ui,
+                            clear_btn_rect,
+                            Id::new(index.to_string() + "remove_variable_btn"),
+                        )
+                        .clicked()
+                        {
+                            remove = Some(index);
+                        }
+                        ui.end_row();
+                    }
+                });
+            if let Some(index) = remove {
+                self.variables.remove(index);
+            }
+            ui.add_space(5.0);
+            ui.vertical_centered(|ui| {
+                ui.style_mut().visuals.widgets.hovered.expansion = 2.0;
+                let next_pos = ui.next_widget_position();
+                let add_btn_rect =
+                    Rect::from_min_max(next_pos, next_pos.add(Vec2::splat(row_height / 1.5)));
+                if components::add_button(ui, add_btn_rect, Id::new("add_variable_button"))
+                    .clicked()
+                {
+                    self.variables.push(("".to_string(), "".to_string()));
+                }
+            });
+        });
+    }
+}
+
+/// Split text into literal text and `{{name}}` placeholders.
+/// The placeholder item is `(raw text, Some(trimmed name))`,a `{{` without `}}` is treated as literal text.
+fn split_placeholder(text: &str) -> Vec<(&str, Option<&str>)> {
+    let mut result = vec![];
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        match rest[start + 2..].find("}}") {
+            None => break,
+            Some(len) => {
+                let end = start + 2 + len + 2;
+                if start > 0 {
+                    result.push((&rest[..start], None));
+                }
+                result.push((&rest[start..end], Some(rest[start + 2..end - 2].trim())));
+                rest = &rest[end..];
+            }
+        }
+    }
+    if !rest.is_empty() {
+        result.push((rest, None));
+    }
+    result
+}
+
+/// Replace all `{{name}}` placeholders by variables,unresolved placeholders are kept as they are.
+pub fn substitute(text: &str, variables: &Variables) -> String {
+    split_placeholder(text)
+        .into_iter()
+        .map(
+            |(raw, name)| match name.and_then(|name| variables.get(name)) {
+                None => raw,
+                Some(value) => value.as_str(),
+            },
+        )
+        .collect()
+}
+
+/// Names of placeholders which can't be resolved by variables.
+pub fn unresolved(text: &str, variables: &Variables) -> Vec<String> {
+    split_placeholder(text)
+        .into_iter()
+        .filter_map(|(_, name)| name)
+        .filter(|name| !variables.contains_key(*name))
+        .map(|name| name.to_string())
+        .collect()
+}
+
+/// Layout text with placeholders highlighted,unresolved ones are painted in crimson.
+pub fn highlight(text: &str, variables: &Variables, font_id: FontId, wrap_width: f32) -> LayoutJob {
+    let mut job = LayoutJob::default();
+    for (raw, name) in split_placeholder(text) {
+        let color = match name {
+            None => color::BLACK,
+            Some(name) if variables.contains_key(name) => color::DODER_BLUE,
+            Some(_) => color::CRIMSON,
+        };
+        job.append(raw, 0.0, TextFormat::simple(font_id.clone(), color));
+    }
+    job.wrap.max_width = wrap_width;
+    job
+}
+
+/// Single line text edit which highlight placeholders.
+pub fn variable_text_edit(
+    ui: &mut Ui,
+    text: &mut String,
+    variables: &Variables,
+    width: f32,
+) -> eframe::egui::Response {
+    let mut layouter = |ui: &Ui, string: &str, wrap_width: f32| {
+        let font_id = FontSelection::default().resolve(ui.style());
+        ui.fonts()
+            .layout_job(highlight(string, variables, font_id, wrap_width))
+    };
+    ui.add(
+        TextEdit::singleline(text)
+            .desired_width(width)
+            .layouter(&mut layouter),
+    )
+}
+
+#[test]
+fn test_substitute() {
+    let variables: Variables = [
+        ("host".to_string(), "localhost".to_string()),
+        ("token".to_string(), "abc".to_string()),
+    ]
+    .into();
+
+    assert_eq!(
+        substitute("http://{{host}}/login?t={{ token }}", &variables),
+        "http://localhost/login?t=abc"
+    );
+    assert_eq!(
+        substitute("{{unknown}}/{{host", &variables),
+        "{{unknown}}/{{host"
+    );
+    assert_eq!(substitute("", &variables), "");
+    assert_eq!(
+        unresolved("{{host}}/{{unknown}}/{{ other }}", &variables),
+        vec!["unknown".to_string(), "other".to_string()]
+    );
+}
@@ -0,0 +1,576 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::{Local, TimeZone};
+use eframe::egui::text::LayoutJob;
+use eframe::egui::{Context, FontSelection, Grid, ScrollArea, TextEdit, TextFormat, Ui, Window};
+use serde::{Deserialize, Serialize};
+
+use crate::color;
+use crate::request::auth::Auth;
+use crate::request::body::BodyKind;
+use crate::request::http::{get_uuid, FormParamType, Http, RequestResult};
+use crate::request::response::format_duration;
+
+/// Entries more than this are dropped from the oldest.
+const MAX_ENTRIES: usize = 100;
+
+/// Response body longer than this is truncated when saved.
+const MAX_BODY: usize = 16 * 1024;
+
+/// Lines more than this are not compared line by line.
+const MAX_DIFF_LINES: usize = 2000;
+
+/// Sent requests,newest first.
+#[derive(Deserialize, Serialize, Default)]
+pub struct History {
+    entries: Vec<Entry>,
+    #[serde(skip)]
+    pub show_history_window: bool,
+    #[serde(skip)]
+    filter: String,
+    /// Id of entry shown in detail.
+    #[serde(skip)]
+    selected: Option<String>,
+    /// Ids of entries which are marked to compare.
+    #[serde(skip)]
+    compare: Vec<String>,
+    /// Error of re-opening entry,such as the method is invalid.
+    #[serde(skip)]
+    error: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct Entry {
+    id: String,
+    /// Unix timestamp in seconds.
+    time: i64,
+    name: String,
+    request: Snapshot,
+    result: Result<ResponseSnapshot, String>,
+}
+
+/// Request with placeholders resolved,as it's sent.
+#[derive(Deserialize, Serialize, Clone, Default, Debug, PartialEq, Eq)]
+pub struct Snapshot {
+    pub method: String,
+    pub url: String,
+    pub header: Vec<(String, String)>,
+    pub body: String,
+    pub form: Vec<(String, String, Option<PathBuf>)>,
+    /// Auth which is applied when sending,it's kept to re-open request with it.
+    #[serde(default)]
+    pub auth: Auth,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+struct ResponseSnapshot {
+    status: u16,
+    elapsed: Duration,
+    size: Option<u64>,
+    header: Vec<(String, String)>,
+    body: String,
+    truncated: bool,
+}
+
+impl Entry {
+    pub fn new(name: &str, request: Snapshot, result: &RequestResult) -> Self {
+        Self {
+            id: get_uuid(),
+            time: Local::now().timestamp(),
+            name: name.to_string(),
+            request,
+            result: match result {
+                Err(err) => Err(err.to_string()),
+                Ok(response) => {
+                    let body = match response.kind {
+                        BodyKind::Text => response.body.clone(),
+                        _ => format!("<{} bytes of binary body>", response.bytes.len()),
+                    };
+                    let mut end = body.len().min(MAX_BODY);
+                    while !body.is_char_boundary(end) {
+                        end -= 1;
+                    }
+                    Ok(ResponseSnapshot {
+                        status: response.code.as_u16(),
+                        elapsed: response.elapsed,
+                        size: response.size,
+                        header: response.headers.clone(),
+                        body: body[..end].to_string(),
+                        truncated: end < body.len(),
+                    })
+                }
+            },
+        }
+    }
+
+    fn time(&self) -> String {
+        match Local.timestamp_opt(self.time, 0).single() {
+            None => "-".to_string(),
+            Some(time) => time.format("%Y-%m-%d %H:%M:%S").to_string(),
+        }
+    }
+
+    fn status(&self) -> String {
+        match &self.result {
+            Ok(response) => response.status.to_string(),
+            Err(_) => "Error".to_string(),
+        }
+    }
+
+    /// Match url,or status such as `404`,`4xx` and `error`.
+    fn matches(&self, filter: &str) -> bool {
+        let filter = filter.trim().to_lowercase();
+        if filter.is_empty() {
+            return true;
+        }
+        if self.request.url.to_lowercase().contains(&filter) {
+            return true;
+        }
+        // `x` is a wildcard only in status pattern such as `4xx`.
+        let is_pattern = filter.len() <= 3
+            && filter.chars().enumerate().all(|(i, c)| match i {
+                0 => ('1'..='5').contains(&c),
+                _ => c.is_ascii_digit() || c == 'x',
+            });
+        let status = self.status().to_lowercase();
+        match is_pattern {
+            true => filter
+                .chars()
+                .zip(status.chars())
+                .all(|(f, s)| f == s || (f == 'x' && s.is_ascii_digit())),
+            false => status == filter,
+        }
+    }
+
+    /// Create a new request which is the same as this entry.
+    fn to_http(&self) -> Result<Http, String> {
+        let content_type = match self
+            .request
+            .header
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case("content-type"))
+        {
+            Some((_, value)) => value.clone(),
+            None if !self.request.form.is_empty() => "multipart/form-data".to_string(),
+            None => "".to_string(),
+        };
+        // Content type of json and form data is set by param type.
+        let header = self
+            .request
+            .header
+            .iter()
+            .filter(|(key, _)| {
+                !key.eq_ignore_ascii_case("content-type")
+                    || !(content_type.contains("application/json")
+                        || content_type.contains("multipart/form-data"))
+            })
+            .cloned()
+            .collect();
+        let mut http = Http::from_curl(
+            self.request.url.clone(),
+            self.request.method.clone(),
+            header,
+            self.request.body.clone(),
+            self.request
+                .form
+                .iter()
+                .map(|(key, value, path)| {
+                    let typ = match path {
+                        None => FormParamType::Text,
+                        Some(_) => FormParamType::File,
+                    };
+                    (key.clone(), value.clone(), path.clone(), typ)
+                })
+                .collect(),
+            content_type,
+            self.request.auth.clone(),
+        )?;
+        http.set_name(&format!("{} ({})", self.name, self.time()));
+        Ok(http)
+    }
+}
+
+impl History {
+    pub fn push(&mut self, entry: Entry) {
+        self.entries.insert(0, entry);
+        self.entries.truncate(MAX_ENTRIES);
+    }
+
+    fn find(&self, id: &str) -> Option<&Entry> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+
+    /// `open` is called with request re-opened from history.
+    pub fn draw_history_window(&mut self, ctx: &Context, open: impl FnOnce(Http)) {
+        let mut show = self.show_history_window;
+        let mut reopen = None;
+        Window::new("History")
+            .resizable(true)
+            .open(&mut show)
+            .collapsible(false)
+            .default_size([900.0, 500.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(
+                        TextEdit::singleline(&mut self.filter)
+                            .hint_text("Filter by URL or status,such as 4xx")
+                            .desired_width(300.0),
+                    );
+                    if ui.button("Clear history").clicked() {
+                        self.entries.clear();
+                        self.selected = None;
+                        self.compare.clear();
+                    }
+                    if self.compare.len() == 2 && ui.button("Stop comparing").clicked() {
+                        self.compare.clear();
+                    }
+                });
+                if let Some(error) = &self.error {
+                    ui.colored_label(color::CRIMSON, error);
+                }
+                ui.separator();
+                if self.entries.is_empty() {
+                    ui.label("No request has been sent");
+                    return;
+                }
+                ui.horizontal_top(|ui| {
+                    ui.vertical(|ui| {
+                        ui.set_width(400.0);
+                        self.list_view(ui);
+                    });
+                    ui.separator();
+                    ui.vertical(|ui| {
+                        ScrollArea::vertical()
+                            .id_source("history_detail_scroll")
+                            .show(ui, |ui| {
+                                if let [a, b] = self.compare.as_slice() {
+                                    if let (Some(a), Some(b)) = (self.find(a), self.find(b)) {
+                                        compare_view(ui, a, b);
+                                        return;
+                                    }
+                                }
+                                if let Some(entry) =
+                                    self.selected.as_ref().and_then(|id| self.find(id))
+                                {
+                                    if ui.button("Open as request").clicked() {
+                                        reopen = Some(entry.to_http());
+                                    }
+                                    ui.add_space(5.0);
+                                    detail_view(ui, entry);
+                                }
+                            });
+                    });
+                });
+            });
+        self.show_history_window = show;
+        match reopen {
+            Some(Ok(http)) => {
+                self.error = None;
+                open(http);
+            }
+            Some(Err(err)) => self.error = Some(format!("Failed to open request: {}", err)),
+            None => {}
+        }
+    }
+
+    fn list_view(&mut self, ui: &mut Ui) {
+        ui.label("Check two entries to compare them.");
+        ScrollArea::vertical()
+            .id_source("history_list_scroll")
+            .show(ui, |ui| {
+                for entry in self
+                    .entries
+                    .iter()
+                    .filter(|entry| entry.matches(&self.filter))
+                {
+                    ui.horizontal(|ui| {
+                        let mut checked = self.compare.contains(&entry.id);
+                        if ui.checkbox(&mut checked, "").changed() {
+                            if checked {
+                                self.compare.push(entry.id.clone());
+                                if self.compare.len() > 2 {
+                                    self.compare.remove(0);
+                                }
+                            } else {
+                                self.compare.retain(|id| *id != entry.id);
+                            }
+                        }
+                        let selected = self.selected.as_ref() == Some(&entry.id);
+                        let label = ui.selectable_label(
+                            selected,
+                            format!(
+                                "{} {} {} {}",
+                                entry.time(),
+                                entry.status(),
+                                entry.request.method,
+                                entry.request.url
+                            ),
+                        );
+                        if label.clicked() {
+                            self.selected = Some(entry.id.clone());
+                            // Show the detail instead of comparison.
+                            if self.compare.len() == 2 {
+                                self.compare.clear();
+                            }
+                        }
+                    });
+                }
+            });
+    }
+}
+
+fn detail_view(ui: &mut Ui, entry: &Entry) {
+    Grid::new("history_detail_grid")
+        .num_columns(2)
+        .striped(true)
+        .show(ui, |ui| {
+            for (key, value) in summary(entry) {
+                ui.label(key);
+                ui.label(value);
+                ui.end_row();
+            }
+        });
+    ui.add_space(5.0);
+    ui.collapsing("Request headers", |ui| {
+        text_view(ui, &header_text(&entry.request.header))
+    });
+    if !entry.request.body.is_empty() || !entry.request.form.is_empty() {
+        ui.collapsing("Request body", |ui| {
+            text_view(ui, &body_text(&entry.request))
+        });
+    }
+    match &entry.result {
+        Err(err) => {
+            ui.colored_label(color::CRIMSON, err);
+        }
+        Ok(response) => {
+            ui.collapsing("Response headers", |ui| {
+                text_view(ui, &header_text(&response.header))
+            });
+            ui.label(if response.truncated {
+                format!("Response body (first {} bytes)", MAX_BODY)
+            } else {
+                "Response body".to_string()
+            });
+            text_view(ui, &response.body);
+        }
+    }
+}
+
+fn compare_view(ui: &mut Ui, a: &Entry, b: &Entry) {
+    Grid::new("history_compare_grid")
+        .num_columns(3)
+        .striped(true)
+        .show(ui, |ui| {
+            for ((key, a), (_, b)) in summary(a).into_iter().zip(summary(b)) {
+                ui.label(key);
+                if a == b {
+                    ui.label(a);
+                    ui.label(b);
+                } else {
+                    ui.colored_label(color::CRIMSON, a);
+                    ui.colored_label(color::SEA_GREEN, b);
+                }
+                ui.end_row();
+            }
+        });
+    ui.add_space(5.0);
+    let response_text = |entry: &Entry, header: bool| match &entry.result {
+        Err(err) => err.clone(),
+        Ok(response) if header => header_text(&response.header),
+        Ok(response) => response.body.clone(),
+    };
+    ui.label("Response headers");
+    diff_view(ui, &response_text(a, true), &response_text(b, true));
+    ui.add_space(5.0);
+    ui.label("Response body");
+    diff_view(ui, &response_text(a, false), &response_text(b, false));
+}
+
+fn summary(entry: &Entry) -> Vec<(&str, String)> {
+    let mut result = vec![
+        ("Name", entry.name.clone()),
+        ("Time", entry.time()),
+        ("Method", entry.request.method.clone()),
+        ("URL", entry.request.url.clone()),
+        ("Status", entry.status()),
+        ("Auth", entry.request.auth.label().to_string()),
+    ];
+    if let Ok(response) = &entry.result {
+        result.push(("Duration", format_duration(response.elapsed)));
+        result.push((
+            "Size",
+            match response.size {
+                None => format!("{} (body)", response.body.len()),
+                Some(size) => size.to_string(),
+            },
+        ));
+    }
+    result
+}
+
+fn header_text(header: &[(String, String)]) -> String {
+    header
+        .iter()
+        .map(|(key, value)| format!("{}: {}", key, value))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn body_text(request: &Snapshot) -> String {
+    if request.form.is_empty() {
+        return request.body.clone();
+    }
+    request
+        .form
+        .iter()
+        .map(|(key, value, path)| match path {
+            None => format!("{}={}", key, value),
+            Some(path) => format!("{}=@{}", key, path.display()),
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn text_view(ui: &mut Ui, text: &str) {
+    let mut text = text;
+    ui.add(TextEdit::multiline(&mut text).desired_width(f32::INFINITY));
+}
+
+/// Show lines removed from `a` in crimson and lines added in `b` in green.
+fn diff_view(ui: &mut Ui, a: &str, b: &str) {
+    let font_id = FontSelection::default().resolve(ui.style());
+    let mut job = LayoutJob::default();
+    for (tag, line) in diff_lines(a, b) {
+        let (prefix, color) = match tag {
+            Diff::Same => ("  ", color::BLACK),
+            Diff::Removed => ("- ", color::CRIMSON),
+            Diff::Added => ("+ ", color::SEA_GREEN),
+        };
+        job.append(
+            &format!("{}{}\n", prefix, line),
+            0.0,
+            TextFormat::simple(font_id.clone(), color),
+        );
+    }
+    ui.group(|ui| {
+        ui.set_width(ui.available_width());
+        ui.label(job);
+    });
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Diff {
+    Same,
+    Removed,
+    Added,
+}
+
+/// Line diff by longest common subsequence.
+fn diff_lines<'a>(a: &'a str, b: &'a str) -> Vec<(Diff, &'a str)> {
+    let a: Vec<&str> = a.lines().collect();
+    let b: Vec<&str> = b.lines().collect();
+    if a.len() > MAX_DIFF_LINES || b.len() > MAX_DIFF_LINES {
+        let mut result: Vec<(Diff, &str)> =
+            a.into_iter().map(|line| (Diff::Removed, line)).collect();
+        result.extend(b.into_iter().map(|line| (Diff::Added, line)));
+        return result;
+    }
+    // lengths[i][j] is length of LCS of a[i..] and b[j..].
+    let mut lengths = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lengths[i][j] = if a[i] == b[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut result = vec![];
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            result.push((Diff::Same, a[i]));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            result.push((Diff::Removed, a[i]));
+            i += 1;
+        } else {
+            result.push((Diff::Added, b[j]));
+            j += 1;
+        }
+    }
+    result.extend(a[i..].iter().map(|line| (Diff::Removed, *line)));
+    result.extend(b[j..].iter().map(|line| (Diff::Added, *line)));
+    result
+}
+
+#[test]
+fn test_history() {
+    let entry = |url: &str, result: Result<ResponseSnapshot, String>| Entry {
+        id: get_uuid(),
+        time: 0,
+        name: "test".to_string(),
+        request: Snapshot {
+            method: "GET".to_string(),
+            url: url.to_string(),
+            ..Default::default()
+        },
+        result,
+    };
+    let response = |status: u16| ResponseSnapshot {
+        status,
+        elapsed: Duration::from_millis(10),
+        size: None,
+        header: vec![],
+        body: "".to_string(),
+        truncated: false,
+    };
+    let mut history = History::default();
+    for index in 0..MAX_ENTRIES + 5 {
+        history.push(entry(
+            &format!("http://localhost/{}", index),
+            Ok(response(200)),
+        ));
+    }
+    assert_eq!(history.entries.len(), MAX_ENTRIES);
+    assert_eq!(
+        history.entries[0].request.url,
+        format!("http://localhost/{}", MAX_ENTRIES + 4)
+    );
+
+    let not_found = entry("http://localhost/users?id=1", Ok(response(404)));
+    assert!(not_found.matches("4xx"));
+    assert!(not_found.matches("404"));
+    assert!(not_found.matches("/USERS"));
+    assert!(!not_found.matches("200"));
+    assert!(not_found.matches("40"));
+    assert!(!not_found.matches("x"));
+    assert!(!not_found.matches("xxx"));
+    assert!(!not_found.matches("usersx"));
+    assert!(!not_found.matches("5xx"));
+    assert!(entry("http://localhost", Err("timeout".to_string())).matches("error"));
+
+    let mut authorized = entry("http://localhost/me", Ok(response(200)));
+    authorized.request.auth = Auth::Bearer {
+        token: "abc".to_string(),
+    };
+    let curl = authorized
+        .to_http()
+        .unwrap()
+        .to_curl(&Default::default(), &Default::default());
+    assert!(curl.contains("Authorization: Bearer abc"), "{}", curl);
+
+    assert_eq!(
+        diff_lines("a\nb\nc", "a\nc\nd"),
+        vec![
+            (Diff::Same, "a"),
+            (Diff::Removed, "b"),
+            (Diff::Same, "c"),
+            (Diff::Added, "d"),
+        ]
+    );
+}
@@ -1,37 +1,83 @@
 // #![feature(vec_retain_mut)]
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+use clap::Parser;
 use eframe::egui::{CentralPanel, ScrollArea, Style, Visuals};
 use eframe::{egui, App, Frame, Storage};
 use serde::{Deserialize, Serialize};
 
 use request::http::Http;
 
+use crate::cli::Cli;
+use crate::collection::{Collections, Node};
+use crate::cookie::Cookies;
 use crate::curl::Curl;
-use crate::request::{ClickType, Request};
+use crate::environment::Environments;
+use crate::history::History;
+use crate::postman::Postman;
+use crate::request::RequestContext;
+use crate::runner::Runner;
 use crate::setting::Settings;
 use crate::style::WeaverStyle;
 
+mod cli;
+mod collection;
 mod color;
 mod components;
+mod cookie;
 mod curl;
+mod environment;
+mod history;
+mod postman;
+mod report;
 mod request;
+mod runner;
 mod setting;
 mod style;
 
 /// We derive Deserialize/Serialize so we can persist app state on shutdown.
 #[derive(Deserialize, Serialize, Default)]
 pub struct Weaver {
+    /// Flat request list saved by older version,moved into `collections` after loading.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     requests: Vec<Http>,
-    active: usize,
+    #[serde(default)]
+    collections: Collections,
     settings: Settings,
+    // `default` keeps state saved by older version loadable.
+    #[serde(default)]
+    environments: Environments,
+    #[serde(default)]
+    history: History,
+    #[serde(default)]
+    cookies: Cookies,
+    #[serde(default)]
+    runner: Runner,
     #[serde(skip)]
     curl: Curl,
+    #[serde(skip)]
+    postman: Postman,
     // TODO Make it out of `Weaver` struct.Use lazy_static maybe better.
     #[serde(skip)]
     style: Option<WeaverStyle>,
 }
 
+impl Weaver {
+    /// Name of active environment,which cookies are kept for.
+    fn session(&self) -> String {
+        match self.environments.active() {
+            None => "".to_string(),
+            Some(environment) => environment.name.clone(),
+        }
+    }
+
+    /// Upgrade state saved by older version.
+    pub fn migrate(&mut self) {
+        self.collections.migrate(std::mem::take(&mut self.requests));
+        self.cookies.restore();
+    }
+}
+
 impl App for Weaver {
     /// Called each time the UI needs repainting, which may be many times per second.
     /// Put your widgets into a `SidePanel`, `TopPanel`, `CentralPanel`, `Window` or `Area`.
@@ -41,17 +87,74 @@ impl App for Weaver {
         // ctx.set_style()
         self.settings.set(ctx);
         self.settings.draw_settings_window(ctx);
+        self.environments.draw_environment_window(ctx);
+        self.collections.draw_folder_window(ctx);
+        let session = self.session();
+        self.cookies.draw_cookies_window(ctx, &session);
+        if let Some(id) = self.collections.run.take() {
+            if let Some((_, _, ids)) = self
+                .collections
+                .folders()
+                .into_iter()
+                .find(|(folder, _, _)| *folder == id)
+            {
+                self.runner.open(ids);
+            }
+        }
+        let mut updates = Default::default();
+        self.runner.draw_runner_window(
+            ctx,
+            &self.collections,
+            &mut RequestContext {
+                variables: &self.environments.variables(),
+                defaults: &Default::default(),
+                history: &mut self.history,
+                settings: &self.settings,
+                session: &session,
+                updates: &mut updates,
+            },
+        );
+        self.environments.update(updates);
+        self.history.draw_history_window(ctx, |http| {
+            self.collections.add(Node::Http(Box::new(http)), None);
+        });
         self.curl.draw_curl_window(ctx, |http| {
-            self.requests.insert(0, http);
-            self.active = 0;
+            self.collections.add(Node::Http(Box::new(http)), None);
         });
+        self.postman.draw_report_window(ctx);
 
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             // The top panel is often a good place for a menu bar:
             egui::menu::bar(ui, |ui| {
+                ui.menu_button("File", |ui| {
+                    ui.menu_button("Import", |ui| {
+                        if ui.button("Postman").clicked() {
+                            ui.close_menu();
+                            if let Some(imported) = self.postman.import_file() {
+                                self.collections.add(Node::Folder(imported.folder), None);
+                                if let Some(environment) = imported.environment {
+                                    self.environments.add(environment);
+                                }
+                            }
+                        }
+                    });
+                });
                 ui.menu_button("New", |ui| {
                     if ui.button("Http").clicked() {
-                        self.requests.insert(0, Http::default());
+                        self.collections.add(Node::Http(Box::default()), None);
+                        ui.close_menu();
+                    }
+                    if ui.button("WebSocket").clicked() {
+                        self.collections.add(Node::WebSocket(Box::default()), None);
+                        ui.close_menu();
+                    }
+                    if ui.button("GraphQL").clicked() {
+                        self.collections.add(Node::GraphQL(Box::default()), None);
+                        ui.close_menu();
+                    }
+                    if ui.button("Collection").clicked() {
+                        self.collections.add_folder(None);
+                        ui.close_menu();
                     }
                     if ui.button("From cURL").clicked() {
                         self.curl.show_curl_window = true;
@@ -62,7 +165,7 @@ impl App for Weaver {
                 #[cfg(debug_assertions)]
                 ui.menu_button("Dev", |ui| {
                     if ui.button("Remove All").clicked() {
-                        self.requests.clear();
+                        self.collections.clear();
                     }
                 });
 
@@ -71,6 +174,20 @@ impl App for Weaver {
                         self.settings.show_settings = true
                     }
                 });
+
+                if ui.button("History").clicked() {
+                    self.history.show_history_window = true;
+                }
+
+                if ui.button("Cookies").clicked() {
+                    self.cookies.show_cookies_window = true;
+                }
+
+                if ui.button("Runner").clicked() {
+                    self.runner.show_runner_window = true;
+                }
+
+                self.environments.select_menu(ui);
             });
         });
         egui::SidePanel::left("request_list")
@@ -78,24 +195,11 @@ impl App for Weaver {
             .show(ctx, |ui| {
                 ui.add_space(2.0);
                 ScrollArea::vertical().show(ui, |ui| {
-                    // TODO Change to `selectable_value`
-                    // This variable used for save `active request` when click.
-                    // Worry about out of bounds is unnecessary,because active will be set to 0 after remove any `request`.Someday maybe change this implementation.
-                    let mut index: usize = 0;
-                    let style = &self.style.as_ref().unwrap();
-                    self.requests.retain(|request| {
-                        let mut result = true;
-
-                        match request.request_name_view(self.active == index, ui, style) {
-                            ClickType::Click => {
-                                self.active = index;
-                            }
-                            ClickType::Delete => result = false,
-                            _ => {}
-                        };
-                        index += 1;
-                        result
-                    });
+                    self.collections.sidebar(
+                        ui,
+                        self.style.as_ref().unwrap(),
+                        &self.environments.variables(),
+                    );
                 });
 
                 // ui.with_layout(egui::Layout::bottom_up(egui::Align::LEFT), |ui| {
@@ -109,23 +213,61 @@ impl App for Weaver {
                 // });
             });
 
-        CentralPanel::default().show(ctx, |ui| match self.requests.get_mut(self.active) {
+        let variables = self.environments.variables();
+        let mut updates = Default::default();
+        CentralPanel::default().show(ctx, |ui| match self.collections.active_mut() {
             None => {}
-            Some(request) => {
+            Some((request, defaults)) => {
                 ScrollArea::vertical().show(ui, |ui| {
-                    request.view(ui);
+                    request.view(
+                        ui,
+                        &mut RequestContext {
+                            variables: &variables,
+                            defaults: &defaults,
+                            history: &mut self.history,
+                            settings: &self.settings,
+                            session: &session,
+                            updates: &mut updates,
+                        },
+                    );
                 });
             }
         });
+        self.environments.update(updates);
     }
 
     fn save(&mut self, storage: &mut dyn Storage) {
         // TODO change key in feature
+        self.cookies.save();
         eframe::set_value(storage, eframe::APP_KEY, self);
     }
 }
 
+/// Release build on Windows has no console,attach the one of terminal to print output of subcommands.
+/// Window is launched without arguments,others are subcommands,help or errors of them.
+#[cfg(all(windows, not(debug_assertions)))]
+fn attach_console() {
+    if std::env::args_os().len() <= 1 {
+        return;
+    }
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    // It fails if launched without terminal,output is discarded then.
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
 fn main() {
+    #[cfg(all(windows, not(debug_assertions)))]
+    attach_console();
+    if let Some(command) = Cli::parse().command {
+        std::process::exit(command.execute());
+    }
+
     let native_options = eframe::NativeOptions::default();
     eframe::run_native(
         "weaver",
@@ -135,6 +277,7 @@ fn main() {
                 None => Default::default(),
                 Some(storage) => eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default(),
             };
+            weaver.migrate();
             let context = &creation_context.egui_ctx;
             let mut visuals = Visuals::light();
             visuals.widgets.open.rounding = Default::default();
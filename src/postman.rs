@@ -0,0 +1,666 @@
+use std::path::PathBuf;
+
+use eframe::egui::{Context, ScrollArea, Window};
+use serde_json::Value;
+
+use crate::collection::{Folder, Node};
+use crate::color;
+use crate::environment::Environment;
+use crate::request::auth::{ApiKeyLocation, Auth};
+use crate::request::http::{FormParamType, Http, Method};
+
+/// Import of Postman collection v2.1,report of the last import is shown in a window.
+#[derive(Default)]
+pub struct Postman {
+    pub show_report_window: bool,
+    report: Option<Result<Report, String>>,
+}
+
+/// Result of importing a collection.
+pub struct Imported {
+    /// Collection as a top level folder.
+    pub folder: Folder,
+    /// Collection variables as an environment named after collection.
+    pub environment: Option<Environment>,
+    pub report: Report,
+}
+
+/// What is imported and what is left out.
+#[derive(Default, Clone)]
+pub struct Report {
+    pub name: String,
+    pub requests: usize,
+    pub folders: usize,
+    pub variables: usize,
+    /// Unsupported parts,such as `login: Pre-request script isn't supported`.
+    pub unsupported: Vec<String>,
+}
+
+impl Postman {
+    /// Pick a collection file and import it,the report is shown after.
+    pub fn import_file(&mut self) -> Option<Imported> {
+        let path = rfd::FileDialog::new()
+            .add_filter("Postman collection", &["json"])
+            .pick_file()?;
+        self.show_report_window = true;
+        match import(&path) {
+            Ok(imported) => {
+                self.report = Some(Ok(imported.report.clone()));
+                Some(imported)
+            }
+            Err(err) => {
+                self.report = Some(Err(err));
+                None
+            }
+        }
+    }
+
+    pub fn draw_report_window(&mut self, ctx: &Context) {
+        let report = match &self.report {
+            None => return,
+            Some(report) => report,
+        };
+        Window::new("Import from Postman")
+            .resizable(true)
+            .open(&mut self.show_report_window)
+            .collapsible(false)
+            .show(ctx, |ui| match report {
+                Err(err) => {
+                    ui.colored_label(color::CRIMSON, err);
+                }
+                Ok(report) => {
+                    ui.colored_label(
+                        color::SEA_GREEN,
+                        format!(
+                            "Imported {}: {} requests,{} folders,{} variables",
+                            report.name, report.requests, report.folders, report.variables
+                        ),
+                    );
+                    if report.variables > 0 {
+                        ui.label(format!(
+                            "Variables are in environment {},select it to use them",
+                            report.name
+                        ));
+                    }
+                    if !report.unsupported.is_empty() {
+                        ui.add_space(5.0);
+                        ui.label(format!("Not imported ({})", report.unsupported.len()));
+                        ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                            for line in &report.unsupported {
+                                ui.colored_label(color::DARK_ORANGE, line);
+                            }
+                        });
+                    }
+                }
+            });
+    }
+}
+
+fn import(path: &PathBuf) -> Result<Imported, String> {
+    let text =
+        std::fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+    parse_postman(&text)
+}
+
+/// Parse Postman collection v2.1,v2.0 is also accepted.
+pub fn parse_postman(text: &str) -> Result<Imported, String> {
+    let collection: Value = serde_json::from_str(text).map_err(|err| err.to_string())?;
+    let info = collection
+        .get("info")
+        .ok_or("Not a Postman collection,`info` is missing")?;
+    let schema = str_of(info, "schema");
+    if !schema.contains("/v2.") {
+        return Err(format!(
+            "Only Postman collection v2.1 is supported,schema is {:?}",
+            schema
+        ));
+    }
+    let mut report = Report {
+        name: match str_of(info, "name") {
+            "" => "Postman".to_string(),
+            name => name.to_string(),
+        },
+        ..Default::default()
+    };
+    let name = report.name.clone();
+    unsupported_events(&collection, &name, &mut report);
+    let auth = parse_auth(collection.get("auth"), &name, &Auth::None, &mut report);
+    let children = parse_items(&collection, &name, &auth, &mut report);
+
+    let variables: Vec<(String, String)> = collection
+        .get("variable")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|variable| !is_disabled(variable))
+        .map(|variable| (str_of(variable, "key").to_string(), value_of(variable)))
+        .filter(|(key, _)| !key.is_empty())
+        .collect();
+    report.variables = variables.len();
+    let environment = (!variables.is_empty()).then(|| Environment {
+        name: name.clone(),
+        variables,
+    });
+
+    Ok(Imported {
+        folder: Folder::new(&name, children),
+        environment,
+        report,
+    })
+}
+
+fn str_of<'a>(value: &'a Value, key: &str) -> &'a str {
+    value.get(key).and_then(Value::as_str).unwrap_or_default()
+}
+
+/// `value` of a key-value item,which may be a number or boolean.
+fn value_of(item: &Value) -> String {
+    match item.get("value") {
+        None | Some(Value::Null) => "".to_string(),
+        Some(Value::String(value)) => value.clone(),
+        Some(value) => value.to_string(),
+    }
+}
+
+fn is_disabled(item: &Value) -> bool {
+    item.get("disabled").and_then(Value::as_bool) == Some(true)
+}
+
+/// Items of collection or folder,`auth` is inherited from parents.
+fn parse_items(parent: &Value, path: &str, auth: &Auth, report: &mut Report) -> Vec<Node> {
+    let mut nodes = vec![];
+    for item in parent
+        .get("item")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let name = str_of(item, "name");
+        let path = format!("{}/{}", path, name);
+        unsupported_events(item, &path, report);
+        if item.get("item").is_some() {
+            let auth = parse_auth(item.get("auth"), &path, auth, report);
+            report.folders += 1;
+            let children = parse_items(item, &path, &auth, report);
+            nodes.push(Node::Folder(Folder::new(name, children)));
+            continue;
+        }
+        let request = match item.get("request") {
+            None => {
+                report
+                    .unsupported
+                    .push(format!("{}: Item without request", path));
+                continue;
+            }
+            Some(request) => request,
+        };
+        match parse_request(request, &path, auth, report) {
+            Ok(mut http) => {
+                http.set_name(name);
+                report.requests += 1;
+                nodes.push(Node::Http(Box::new(http)));
+            }
+            Err(err) => report.unsupported.push(format!("{}: {}", path, err)),
+        }
+    }
+    nodes
+}
+
+fn unsupported_events(item: &Value, path: &str, report: &mut Report) {
+    for event in item
+        .get("event")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let exec = &event["script"]["exec"];
+        let empty = match exec {
+            Value::Array(lines) => lines
+                .iter()
+                .all(|line| line.as_str().unwrap_or_default().trim().is_empty()),
+            Value::String(line) => line.trim().is_empty(),
+            _ => true,
+        };
+        if empty {
+            continue;
+        }
+        let kind = match str_of(event, "listen") {
+            "prerequest" => "Pre-request script",
+            "test" => "Test script",
+            other => other,
+        };
+        report
+            .unsupported
+            .push(format!("{}: {} isn't supported", path, kind));
+    }
+}
+
+/// Auth of item,`inherited` is used if it has no auth or inherits from parent.
+fn parse_auth(auth: Option<&Value>, path: &str, inherited: &Auth, report: &mut Report) -> Auth {
+    let auth = match auth {
+        None | Some(Value::Null) => return inherited.clone(),
+        Some(auth) => auth,
+    };
+    let typ = str_of(auth, "type");
+    // Parameters are a list of key-value in v2.1,and an object in v2.0.
+    let param = |key: &str| -> String {
+        match auth.get(typ) {
+            Some(Value::Array(params)) => params
+                .iter()
+                .find(|param| str_of(param, "key") == key)
+                .map(value_of)
+                .unwrap_or_default(),
+            Some(Value::Object(params)) => params
+                .get(key)
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            _ => "".to_string(),
+        }
+    };
+    match typ {
+        "inherit" => inherited.clone(),
+        "noauth" => Auth::None,
+        "basic" => Auth::Basic {
+            username: param("username"),
+            password: param("password"),
+        },
+        "digest" => Auth::Digest {
+            username: param("username"),
+            password: param("password"),
+        },
+        "bearer" => Auth::Bearer {
+            token: param("token"),
+        },
+        "apikey" => Auth::ApiKey {
+            key: param("key"),
+            value: param("value"),
+            location: match param("in").as_str() {
+                "query" => ApiKeyLocation::Query,
+                _ => ApiKeyLocation::Header,
+            },
+        },
+        other => {
+            report
+                .unsupported
+                .push(format!("{}: Auth {} isn't supported", path, other));
+            Auth::None
+        }
+    }
+}
+
+fn parse_request(
+    request: &Value,
+    path: &str,
+    auth: &Auth,
+    report: &mut Report,
+) -> Result<Http, String> {
+    // Request may be just an url.
+    if let Value::String(url) = request {
+        return Http::from_curl(
+            url.clone(),
+            "GET".to_string(),
+            vec![],
+            "".to_string(),
+            vec![],
+            "".to_string(),
+            auth.clone(),
+        );
+    }
+    let method = match str_of(request, "method") {
+        "" => "GET".to_string(),
+        method => method.to_ascii_uppercase(),
+    };
+    let mut url = parse_url(request.get("url"));
+    let mut header: Vec<(String, String)> = request
+        .get("header")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|header| !is_disabled(header))
+        .map(|header| (str_of(header, "key").to_string(), value_of(header)))
+        .collect();
+    let content_type = header
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case("content-type"))
+        .map(|(_, value)| value.clone())
+        .unwrap_or_default();
+
+    let mut text_param = "".to_string();
+    let mut form_param = vec![];
+    let mut param_type = content_type.clone();
+    if let Some(body) = request.get("body").filter(|body| !is_disabled(body)) {
+        match str_of(body, "mode") {
+            "raw" => {
+                text_param = str_of(body, "raw").to_string();
+                if body["options"]["raw"]["language"] == "json" {
+                    param_type = "application/json".to_string();
+                }
+            }
+            "urlencoded" => {
+                // Kept as it is,so `{{var}}` isn't encoded.
+                text_param = body["urlencoded"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter(|param| !is_disabled(param))
+                    .map(|param| format!("{}={}", str_of(param, "key"), value_of(param)))
+                    .collect::<Vec<String>>()
+                    .join("&");
+                if content_type.is_empty() {
+                    header.push((
+                        "Content-Type".to_string(),
+                        "application/x-www-form-urlencoded".to_string(),
+                    ));
+                }
+            }
+            "formdata" => {
+                for param in body["formdata"].as_array().into_iter().flatten() {
+                    if is_disabled(param) {
+                        continue;
+                    }
+                    let key = str_of(param, "key").to_string();
+                    match str_of(param, "type") {
+                        "file" => {
+                            // Only one file of a field is kept.
+                            let src = match &param["src"] {
+                                Value::String(src) => Some(src.as_str()),
+                                Value::Array(srcs) => {
+                                    if srcs.len() > 1 {
+                                        report.unsupported.push(format!(
+                                            "{}: Only the first file of form field {} is imported",
+                                            path, key
+                                        ));
+                                    }
+                                    srcs.first().and_then(Value::as_str)
+                                }
+                                _ => None,
+                            };
+                            form_param.push((
+                                key,
+                                "".to_string(),
+                                src.map(PathBuf::from),
+                                FormParamType::File,
+                            ));
+                        }
+                        _ => form_param.push((key, value_of(param), None, FormParamType::Text)),
+                    }
+                }
+                param_type = "multipart/form-data".to_string();
+            }
+            "" => {}
+            mode => report
+                .unsupported
+                .push(format!("{}: Body mode {} isn't supported", path, mode)),
+        }
+    }
+    // Json and form data are sent by any method,other bodies are dropped by query only method.
+    let query_only = Method::from_name(&method).is_some_and(|method| method.is_query_only())
+        && !param_type.contains("application/json")
+        && !param_type.contains("multipart/form-data");
+    if query_only {
+        if !text_param.is_empty() {
+            report
+                .unsupported
+                .push(format!("{}: Body of {} isn't supported", path, method));
+        }
+        // Url may start with a variable,which can't be parsed to take its query.
+        if let Some(query) = request["url"]["query"].as_array() {
+            form_param = query
+                .iter()
+                .filter(|param| !is_disabled(param))
+                .map(|param| {
+                    (
+                        str_of(param, "key").to_string(),
+                        value_of(param),
+                        None,
+                        FormParamType::Text,
+                    )
+                })
+                .collect();
+            if let Some((path, _)) = url.split_once('?') {
+                url = path.to_string();
+            }
+        }
+    }
+    Http::from_curl(
+        url,
+        method,
+        header,
+        text_param,
+        form_param,
+        param_type,
+        parse_auth(request.get("auth"), path, auth, report),
+    )
+}
+
+/// Url of request,path variables like `:id` are replaced by their values or `{{id}}`.
+fn parse_url(url: Option<&Value>) -> String {
+    let url = match url {
+        None => return "".to_string(),
+        Some(Value::String(raw)) => return raw.clone(),
+        Some(url) => url,
+    };
+    let raw = match str_of(url, "raw") {
+        "" => {
+            let join = |key: &str, separator: &str| match &url[key] {
+                Value::Array(parts) => parts
+                    .iter()
+                    .map(|part| match part {
+                        Value::String(part) => part.clone(),
+                        part => str_of(part, "value").to_string(),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(separator),
+                Value::String(part) => part.clone(),
+                _ => "".to_string(),
+            };
+            let mut raw = join("host", ".");
+            if !str_of(url, "protocol").is_empty() {
+                raw = format!("{}://{}", str_of(url, "protocol"), raw);
+            }
+            let path = join("path", "/");
+            if !path.is_empty() {
+                raw = format!("{}/{}", raw, path);
+            }
+            let query: Vec<String> = url["query"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter(|param| !is_disabled(param))
+                .map(|param| format!("{}={}", str_of(param, "key"), value_of(param)))
+                .collect();
+            if !query.is_empty() {
+                raw = format!("{}?{}", raw, query.join("&"));
+            }
+            raw
+        }
+        raw => raw.to_string(),
+    };
+    let variables: Vec<(&str, String)> = url["variable"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|variable| (str_of(variable, "key"), value_of(variable)))
+        .collect();
+    if variables.is_empty() {
+        return raw;
+    }
+    let (path, query) = match raw.split_once('?') {
+        None => (raw.as_str(), None),
+        Some((path, query)) => (path, Some(query)),
+    };
+    let path = path
+        .split('/')
+        .map(|segment| {
+            let value = segment.strip_prefix(':').and_then(|name| {
+                variables
+                    .iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(key, value)| match value.is_empty() {
+                        true => format!("{{{{{}}}}}", key),
+                        false => value.clone(),
+                    })
+            });
+            value.unwrap_or_else(|| segment.to_string())
+        })
+        .collect::<Vec<String>>()
+        .join("/");
+    match query {
+        None => path,
+        Some(query) => format!("{}?{}", path, query),
+    }
+}
+
+#[test]
+fn test_parse_postman() {
+    let collection = r#"{
+      "info": {
+        "name": "Shop",
+        "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json"
+      },
+      "auth": {"type": "basic", "basic": [
+        {"key": "username", "value": "admin"},
+        {"key": "password", "value": "{{password}}"}
+      ]},
+      "variable": [
+        {"key": "host", "value": "https://shop.local"},
+        {"key": "password", "value": "secret"},
+        {"key": "old", "value": "x", "disabled": true}
+      ],
+      "item": [
+        {
+          "name": "Users",
+          "event": [{"listen": "prerequest", "script": {"exec": ["pm.variables.set('a', 1)"]}}],
+          "item": [
+            {
+              "name": "Get user",
+              "request": {
+                "method": "GET",
+                "header": [
+                  {"key": "Accept", "value": "application/json"},
+                  {"key": "X-Debug", "value": "1", "disabled": true}
+                ],
+                "url": {
+                  "raw": "{{host}}/users/:id?expand=true&debug=1",
+                  "query": [
+                    {"key": "expand", "value": "true"},
+                    {"key": "debug", "value": "1", "disabled": true}
+                  ],
+                  "variable": [{"key": "id", "value": "7"}]
+                }
+              }
+            },
+            {
+              "name": "Search",
+              "request": {
+                "method": "GET",
+                "body": {"mode": "raw", "raw": "{\"q\":1}", "options": {"raw": {"language": "json"}}},
+                "url": "{{host}}/search"
+              }
+            },
+            {
+              "name": "Options",
+              "request": {
+                "method": "OPTIONS",
+                "body": {"mode": "raw", "raw": "text"},
+                "url": "{{host}}/users"
+              }
+            },
+            {
+              "name": "Head",
+              "request": {
+                "method": "HEAD",
+                "body": {"mode": "raw", "raw": "dropped"},
+                "url": "{{host}}/users"
+              }
+            },
+            {
+              "name": "Create user",
+              "event": [{"listen": "test", "script": {"exec": ["pm.test('ok')"]}}],
+              "request": {
+                "method": "POST",
+                "auth": {"type": "bearer", "bearer": [{"key": "token", "value": "{{token}}"}]},
+                "body": {"mode": "raw", "raw": "{\"name\":\"a\"}", "options": {"raw": {"language": "json"}}},
+                "url": "{{host}}/users"
+              }
+            }
+          ]
+        },
+        {
+          "name": "Login",
+          "request": {
+            "method": "POST",
+            "auth": {"type": "noauth"},
+            "body": {"mode": "urlencoded", "urlencoded": [
+              {"key": "user", "value": "a"},
+              {"key": "pass", "value": "{{password}}"}
+            ]},
+            "url": {"protocol": "https", "host": ["shop", "local"], "path": ["login"]}
+          }
+        },
+        {
+          "name": "Upload",
+          "request": {
+            "method": "PUT",
+            "auth": {"type": "awsv4", "awsv4": []},
+            "body": {"mode": "formdata", "formdata": [
+              {"key": "file", "type": "file", "src": "/tmp/a.png"},
+              {"key": "note", "type": "text", "value": "hi"}
+            ]},
+            "url": "{{host}}/upload"
+          }
+        },
+        {
+          "name": "Graph",
+          "request": {"method": "POST", "body": {"mode": "graphql", "graphql": {}}, "url": "{{host}}/graphql"}
+        }
+      ]
+    }"#;
+    let imported = parse_postman(collection).unwrap();
+    let report = &imported.report;
+    assert_eq!(
+        (report.requests, report.folders, report.variables),
+        (8, 1, 2)
+    );
+    assert_eq!(
+        report.unsupported,
+        vec![
+            "Shop/Users: Pre-request script isn't supported",
+            "Shop/Users/Head: Body of HEAD isn't supported",
+            "Shop/Users/Create user: Test script isn't supported",
+            "Shop/Upload: Auth awsv4 isn't supported",
+            "Shop/Graph: Body mode graphql isn't supported",
+        ]
+    );
+    assert_eq!(imported.environment.unwrap().variables.len(), 2);
+
+    let curls: Vec<String> = imported
+        .folder
+        .children
+        .iter()
+        .flat_map(|node| match node {
+            Node::Folder(folder) => folder.children.iter().collect(),
+            node => vec![node],
+        })
+        .map(|node| match node {
+            Node::Http(http) => http
+                .to_curl(&Default::default(), &Default::default())
+                .replace(" \\\n  ", " "),
+            _ => "".to_string(),
+        })
+        .collect();
+    assert_eq!(
+        curls,
+        vec![
+            "curl '{{host}}/users/7?expand=true' -L --max-redirs 10 -X GET -H 'Accept: application/json' -u 'admin:{{password}}'",
+            r#"curl '{{host}}/search' -L --max-redirs 10 -X GET -u 'admin:{{password}}' -H 'Content-Type: application/json' --data-raw '{"q":1}'"#,
+            "curl '{{host}}/users' -L --max-redirs 10 -X OPTIONS -u 'admin:{{password}}' --data-raw text",
+            "curl '{{host}}/users' -L --max-redirs 10 --head -u 'admin:{{password}}'",
+            r#"curl '{{host}}/users' -L --max-redirs 10 -X POST -H 'Authorization: Bearer {{token}}' -H 'Content-Type: application/json' --data-raw '{"name":"a"}'"#,
+            "curl https://shop.local/login -L --max-redirs 10 -X POST -H 'Content-Type: application/x-www-form-urlencoded' --data-raw 'user=a&pass={{password}}'",
+            "curl '{{host}}/upload' -L --max-redirs 10 -X PUT -F file=@/tmp/a.png -F note=hi",
+            "curl '{{host}}/graphql' -L --max-redirs 10 -X POST -u 'admin:{{password}}'",
+        ]
+    );
+}
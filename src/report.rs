@@ -0,0 +1,276 @@
+use std::fmt::Write;
+use std::time::Duration;
+
+use clap::ValueEnum;
+use serde_json::json;
+
+use crate::request::assertion::Outcome;
+
+/// Format of report written by `weaver run`.
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Reporter {
+    Junit,
+    Json,
+    Tap,
+}
+
+/// Result of a request which is run from command line.
+pub struct Case {
+    /// Path like `collection/folder/name`.
+    pub path: String,
+    pub method: String,
+    pub url: String,
+    pub status: Option<u16>,
+    pub elapsed: Duration,
+    /// Error of sending request.
+    pub error: Option<String>,
+    pub outcomes: Vec<Outcome>,
+}
+
+impl Case {
+    pub fn passed(&self) -> bool {
+        self.error.is_none() && self.failures().next().is_none()
+    }
+
+    /// Failed assertions as `name: reason`.
+    fn failures(&self) -> impl Iterator<Item = String> + '_ {
+        self.outcomes.iter().filter_map(|outcome| {
+            outcome
+                .error
+                .as_ref()
+                .map(|err| format!("{}: {}", outcome.name, err))
+        })
+    }
+}
+
+impl Reporter {
+    /// Report of all cases,`elapsed` is time of the whole run.
+    pub fn report(&self, cases: &[Case], elapsed: Duration) -> String {
+        match self {
+            Reporter::Junit => junit(cases, elapsed),
+            Reporter::Json => json(cases, elapsed),
+            Reporter::Tap => tap(cases),
+        }
+    }
+}
+
+fn seconds(duration: Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64())
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters aren't allowed in XML 1.0.
+            c if c.is_control() && !matches!(c, '\n' | '\r' | '\t') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn junit(cases: &[Case], elapsed: Duration) -> String {
+    let errors = cases.iter().filter(|case| case.error.is_some()).count();
+    let failures = cases
+        .iter()
+        .filter(|case| case.error.is_none() && !case.passed())
+        .count();
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"weaver\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{}\">",
+        cases.len(),
+        failures,
+        errors,
+        seconds(elapsed)
+    );
+    let _ = writeln!(
+        xml,
+        "  <testsuite name=\"weaver\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{}\">",
+        cases.len(),
+        failures,
+        errors,
+        seconds(elapsed)
+    );
+    for case in cases {
+        // Folder is the class,request is the test.
+        let (class, name) = match case.path.rsplit_once('/') {
+            None => ("weaver".to_string(), case.path.as_str()),
+            Some((folder, name)) => (folder.replace('/', "."), name),
+        };
+        let _ = writeln!(
+            xml,
+            "    <testcase classname=\"{}\" name=\"{}\" time=\"{}\">",
+            escape_xml(&class),
+            escape_xml(&format!("{} {}", case.method, name)),
+            seconds(case.elapsed)
+        );
+        if let Some(err) = &case.error {
+            let _ = writeln!(
+                xml,
+                "      <error type=\"request\" message=\"{}\"/>",
+                escape_xml(err)
+            );
+        }
+        let failures: Vec<String> = case.failures().collect();
+        if !failures.is_empty() {
+            let _ = writeln!(
+                xml,
+                "      <failure type=\"assertion\" message=\"{} of {} assertions failed\">{}</failure>",
+                failures.len(),
+                case.outcomes.len(),
+                escape_xml(&failures.join("\n"))
+            );
+        }
+        let status = case
+            .status
+            .map(|status| status.to_string())
+            .unwrap_or_else(|| "-".to_string());
+        let _ = writeln!(
+            xml,
+            "      <system-out>{}</system-out>",
+            escape_xml(&format!("{} {} {}", status, case.method, case.url))
+        );
+        xml.push_str("    </testcase>\n");
+    }
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+fn json(cases: &[Case], elapsed: Duration) -> String {
+    let passed = cases.iter().filter(|case| case.passed()).count();
+    let requests: Vec<serde_json::Value> = cases
+        .iter()
+        .map(|case| {
+            json!({
+                "name": case.path,
+                "method": case.method,
+                "url": case.url,
+                "status": case.status,
+                "time_ms": case.elapsed.as_millis() as u64,
+                "passed": case.passed(),
+                "error": case.error,
+                "assertions": case.outcomes.iter().map(|outcome| json!({
+                    "name": outcome.name,
+                    "passed": outcome.error.is_none(),
+                    "error": outcome.error,
+                })).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    let report = json!({
+        "total": cases.len(),
+        "passed": passed,
+        "failed": cases.len() - passed,
+        "time_ms": elapsed.as_millis() as u64,
+        "requests": requests,
+    });
+    serde_json::to_string_pretty(&report).unwrap_or_default() + "\n"
+}
+
+/// TAP version 13,details of failure are in YAML block.
+fn tap(cases: &[Case]) -> String {
+    let mut tap = format!("TAP version 13\n1..{}\n", cases.len());
+    for (i, case) in cases.iter().enumerate() {
+        let description = format!("{} {}", case.method, case.path).replace('#', "\\#");
+        if case.passed() {
+            let _ = writeln!(tap, "ok {} - {}", i + 1, description);
+            continue;
+        }
+        let _ = writeln!(tap, "not ok {} - {}", i + 1, description);
+        // Json strings are valid YAML scalars.
+        let quote = |text: &str| serde_json::Value::from(text).to_string();
+        tap.push_str("  ---\n");
+        let _ = writeln!(tap, "  url: {}", quote(&case.url));
+        if let Some(status) = case.status {
+            let _ = writeln!(tap, "  status: {}", status);
+        }
+        let _ = writeln!(tap, "  time_ms: {}", case.elapsed.as_millis());
+        if let Some(err) = &case.error {
+            let _ = writeln!(tap, "  error: {}", quote(err));
+        }
+        let failures: Vec<String> = case.failures().collect();
+        if !failures.is_empty() {
+            tap.push_str("  failures:\n");
+            for failure in failures {
+                let _ = writeln!(tap, "    - {}", quote(&failure));
+            }
+        }
+        tap.push_str("  ...\n");
+    }
+    tap
+}
+
+#[test]
+fn test_report() {
+    let outcome = |name: &str, error: Option<&str>| Outcome {
+        name: name.to_string(),
+        error: error.map(str::to_string),
+    };
+    let cases = vec![
+        Case {
+            path: "api/users/list".to_string(),
+            method: "GET".to_string(),
+            url: "https://example.com/users?a=1&b=2".to_string(),
+            status: Some(200),
+            elapsed: Duration::from_millis(120),
+            error: None,
+            outcomes: vec![outcome("Status is 200", None)],
+        },
+        Case {
+            path: "login".to_string(),
+            method: "POST".to_string(),
+            url: "https://example.com/login".to_string(),
+            status: Some(500),
+            elapsed: Duration::from_millis(5),
+            error: None,
+            outcomes: vec![outcome("Status is 200", Some("Got 500"))],
+        },
+        Case {
+            path: "down".to_string(),
+            method: "GET".to_string(),
+            url: "http://127.0.0.1:1/".to_string(),
+            status: None,
+            elapsed: Duration::from_millis(1),
+            error: Some("Connection refused <os error>".to_string()),
+            outcomes: vec![],
+        },
+    ];
+    let elapsed = Duration::from_millis(126);
+
+    let xml = Reporter::Junit.report(&cases, elapsed);
+    assert!(xml.contains(
+        "<testsuites name=\"weaver\" tests=\"3\" failures=\"1\" errors=\"1\" time=\"0.126\">"
+    ));
+    assert!(xml.contains("<testcase classname=\"api.users\" name=\"GET list\" time=\"0.120\">"));
+    assert!(xml.contains(
+        "<failure type=\"assertion\" message=\"1 of 1 assertions failed\">Status is 200: Got 500</failure>"
+    ));
+    assert!(
+        xml.contains("<error type=\"request\" message=\"Connection refused &lt;os error&gt;\"/>")
+    );
+    assert!(xml.contains("200 GET https://example.com/users?a=1&amp;b=2"));
+
+    let report: serde_json::Value =
+        serde_json::from_str(&Reporter::Json.report(&cases, elapsed)).unwrap();
+    assert_eq!(report["failed"], 2);
+    assert_eq!(report["requests"][0]["time_ms"], 120);
+    assert_eq!(report["requests"][1]["assertions"][0]["error"], "Got 500");
+    assert_eq!(report["requests"][2]["status"], serde_json::Value::Null);
+
+    assert_eq!(
+        Reporter::Tap.report(&cases, elapsed),
+        "TAP version 13\n1..3\n\
+         ok 1 - GET api/users/list\n\
+         not ok 2 - POST login\n  ---\n  url: \"https://example.com/login\"\n  status: 500\n  time_ms: 5\n  \
+         failures:\n    - \"Status is 200: Got 500\"\n  ...\n\
+         not ok 3 - GET down\n  ---\n  url: \"http://127.0.0.1:1/\"\n  time_ms: 1\n  \
+         error: \"Connection refused <os error>\"\n  ...\n"
+    );
+}
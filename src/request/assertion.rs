@@ -0,0 +1,325 @@
+use eframe::egui::{ComboBox, DragValue, Grid, TextEdit, Ui};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::color;
+use crate::environment::{self, Variables};
+use crate::request::body::BodyKind;
+use crate::request::json;
+use crate::request::response::Response;
+
+/// Check of response,text is resolved by variables before checking.
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub enum Assertion {
+    Status(u16),
+    /// Status from first to second,both inclusive.
+    StatusRange(u16, u16),
+    HeaderPresent(String),
+    /// Header name and regex which its value matches.
+    HeaderMatches(String, String),
+    /// Json path and expected value,which is parsed as json or taken as string.
+    JsonEquals(String, String),
+    JsonExists(String),
+    /// Json path and type name such as `string` and `array`.
+    JsonType(String, String),
+    BodyContains(String),
+    /// Total time in milliseconds.
+    TimeUnder(u64),
+}
+
+/// Result of an assertion,which is shown and reported.
+#[derive(Clone, Debug)]
+pub struct Outcome {
+    pub name: String,
+    /// Reason of failure.
+    pub error: Option<String>,
+}
+
+const JSON_TYPES: [&str; 6] = ["string", "number", "boolean", "object", "array", "null"];
+
+impl Assertion {
+    /// Assertions listed in kind select.
+    fn presets() -> [Assertion; 9] {
+        [
+            Assertion::Status(200),
+            Assertion::StatusRange(200, 299),
+            Assertion::HeaderPresent("".to_string()),
+            Assertion::HeaderMatches("".to_string(), "".to_string()),
+            Assertion::JsonEquals("$.".to_string(), "".to_string()),
+            Assertion::JsonExists("$.".to_string()),
+            Assertion::JsonType("$.".to_string(), "string".to_string()),
+            Assertion::BodyContains("".to_string()),
+            Assertion::TimeUnder(1000),
+        ]
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Assertion::Status(_) => "Status equals",
+            Assertion::StatusRange(..) => "Status in range",
+            Assertion::HeaderPresent(_) => "Header present",
+            Assertion::HeaderMatches(..) => "Header matches",
+            Assertion::JsonEquals(..) => "Json path equals",
+            Assertion::JsonExists(_) => "Json path exists",
+            Assertion::JsonType(..) => "Json path type",
+            Assertion::BodyContains(_) => "Body contains",
+            Assertion::TimeUnder(_) => "Time under",
+        }
+    }
+
+    /// Readable description,such as `Status in 200..=299`.
+    pub fn name(&self) -> String {
+        match self {
+            Assertion::Status(status) => format!("Status is {}", status),
+            Assertion::StatusRange(from, to) => format!("Status in {}..={}", from, to),
+            Assertion::HeaderPresent(name) => format!("Header {} is present", name),
+            Assertion::HeaderMatches(name, pattern) => {
+                format!("Header {} matches /{}/", name, pattern)
+            }
+            Assertion::JsonEquals(path, value) => format!("{} equals {}", path, value),
+            Assertion::JsonExists(path) => format!("{} exists", path),
+            Assertion::JsonType(path, typ) => format!("{} is {}", path, typ),
+            Assertion::BodyContains(text) => format!("Body contains {:?}", text),
+            Assertion::TimeUnder(ms) => format!("Time under {} ms", ms),
+        }
+    }
+
+    pub fn check(&self, response: &Response, variables: &Variables) -> Result<(), String> {
+        let resolve = |text: &String| environment::substitute(text, variables);
+        let status = response.code.as_u16();
+        let header = |name: &String| {
+            let name = resolve(name);
+            response
+                .headers
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(&name))
+                .map(|(_, value)| value.as_str())
+                .ok_or_else(|| format!("No header {}", name))
+        };
+        let json = |path: &String| -> Result<Option<&Value>, String> {
+            match &response.json {
+                None => Err("Body isn't json".to_string()),
+                Some(value) => json::select(value, &resolve(path)),
+            }
+        };
+        match self {
+            Assertion::Status(expected) if status == *expected => Ok(()),
+            Assertion::StatusRange(from, to) if (*from..=*to).contains(&status) => Ok(()),
+            Assertion::Status(_) | Assertion::StatusRange(..) => Err(format!("Got {}", status)),
+            Assertion::HeaderPresent(name) => header(name).map(|_| ()),
+            Assertion::HeaderMatches(name, pattern) => {
+                let value = header(name)?;
+                let regex = Regex::new(&resolve(pattern)).map_err(|err| err.to_string())?;
+                match regex.is_match(value) {
+                    true => Ok(()),
+                    false => Err(format!("Got {:?}", value)),
+                }
+            }
+            Assertion::JsonEquals(path, expected) => {
+                let expected = resolve(expected);
+                let expected = serde_json::from_str(&expected).unwrap_or(Value::String(expected));
+                match json(path)? {
+                    Some(value) if *value == expected => Ok(()),
+                    Some(value) => Err(format!("Got {}", value)),
+                    None => Err("Not found".to_string()),
+                }
+            }
+            Assertion::JsonExists(path) => match json(path)? {
+                Some(_) => Ok(()),
+                None => Err("Not found".to_string()),
+            },
+            Assertion::JsonType(path, expected) => match json(path)? {
+                Some(value) if json::type_name(value) == expected => Ok(()),
+                Some(value) => Err(format!("Got {}", json::type_name(value))),
+                None => Err("Not found".to_string()),
+            },
+            Assertion::BodyContains(text) => {
+                if response.kind != BodyKind::Text {
+                    return Err("Body isn't text".to_string());
+                }
+                match response.body.contains(&resolve(text)) {
+                    true => Ok(()),
+                    false => Err("Not found".to_string()),
+                }
+            }
+            Assertion::TimeUnder(ms) => match response.elapsed.as_millis() < *ms as u128 {
+                true => Ok(()),
+                false => Err(format!("Took {} ms", response.elapsed.as_millis())),
+            },
+        }
+    }
+
+    /// Editor of an assertion in a grid row.
+    fn row_view(&mut self, ui: &mut Ui, id: usize) {
+        ComboBox::from_id_source(format!("assertion_kind_{}", id))
+            .selected_text(self.kind())
+            .width(180.0)
+            .show_ui(ui, |ui| {
+                for preset in Self::presets() {
+                    let kind = preset.kind();
+                    // Values are kept if kind isn't changed.
+                    if ui.selectable_label(self.kind() == kind, kind).clicked()
+                        && self.kind() != kind
+                    {
+                        *self = preset;
+                    }
+                }
+            });
+        let text = |ui: &mut Ui, text: &mut String, hint: &str| {
+            ui.add(
+                TextEdit::singleline(text)
+                    .hint_text(hint)
+                    .desired_width(180.0),
+            );
+        };
+        ui.horizontal(|ui| match self {
+            Assertion::Status(status) => {
+                ui.add(DragValue::new(status).clamp_range(100..=599));
+            }
+            Assertion::StatusRange(from, to) => {
+                ui.add(DragValue::new(from).clamp_range(100..=599));
+                ui.label("to");
+                ui.add(DragValue::new(to).clamp_range(100..=599));
+            }
+            Assertion::HeaderPresent(name) => text(ui, name, "Header"),
+            Assertion::HeaderMatches(name, pattern) => {
+                text(ui, name, "Header");
+                text(ui, pattern, "Regex");
+            }
+            Assertion::JsonEquals(path, value) => {
+                text(ui, path, "$.path");
+                text(ui, value, "Json value or text");
+            }
+            Assertion::JsonExists(path) => text(ui, path, "$.path"),
+            Assertion::JsonType(path, typ) => {
+                text(ui, path, "$.path");
+                ComboBox::from_id_source(format!("assertion_type_{}", id))
+                    .selected_text(typ.as_str())
+                    .show_ui(ui, |ui| {
+                        for name in JSON_TYPES {
+                            ui.selectable_value(typ, name.to_string(), name);
+                        }
+                    });
+            }
+            Assertion::BodyContains(body) => text(ui, body, "Text"),
+            Assertion::TimeUnder(ms) => {
+                ui.add(DragValue::new(ms).suffix(" ms"));
+            }
+        });
+    }
+}
+
+/// Check all assertions against response.
+pub fn evaluate(
+    assertions: &[Assertion],
+    response: &Response,
+    variables: &Variables,
+) -> Vec<Outcome> {
+    assertions
+        .iter()
+        .map(|assertion| Outcome {
+            name: assertion.name(),
+            error: assertion.check(response, variables).err(),
+        })
+        .collect()
+}
+
+/// Editor of assertions.
+pub fn view(ui: &mut Ui, assertions: &mut Vec<Assertion>) {
+    let mut remove = None;
+    Grid::new("assertions_grid").num_columns(3).show(ui, |ui| {
+        for (i, assertion) in assertions.iter_mut().enumerate() {
+            assertion.row_view(ui, i);
+            if ui.button("🗑").clicked() {
+                remove = Some(i);
+            }
+            ui.end_row();
+        }
+    });
+    if let Some(i) = remove {
+        assertions.remove(i);
+    }
+    if ui.button("Add assertion").clicked() {
+        assertions.push(Assertion::Status(200));
+    }
+}
+
+/// Pass or fail of each assertion.
+pub fn outcomes_view(ui: &mut Ui, outcomes: &[Outcome]) {
+    let passed = outcomes
+        .iter()
+        .filter(|outcome| outcome.error.is_none())
+        .count();
+    ui.colored_label(
+        match passed == outcomes.len() {
+            true => color::SEA_GREEN,
+            false => color::CRIMSON,
+        },
+        format!("Tests: {}/{} passed", passed, outcomes.len()),
+    );
+    for outcome in outcomes {
+        match &outcome.error {
+            None => ui.colored_label(color::SEA_GREEN, format!("✔ {}", outcome.name)),
+            Some(err) => ui.colored_label(color::CRIMSON, format!("✘ {}: {}", outcome.name, err)),
+        };
+    }
+}
+
+#[test]
+fn test_evaluate() {
+    let body = r#"{"user":{"id":7,"name":"weaver"},"tags":[]}"#;
+    let response = Response {
+        code: reqwest::StatusCode::CREATED,
+        headers: vec![(
+            "Content-Type".to_string(),
+            "application/json; charset=utf-8".to_string(),
+        )],
+        json: serde_json::from_str(body).ok(),
+        body: body.to_string(),
+        elapsed: std::time::Duration::from_millis(120),
+        ..Response::default()
+    };
+    let variables = Variables::from([("id".to_string(), "7".to_string())]);
+    let outcomes = evaluate(
+        &[
+            Assertion::Status(201),
+            Assertion::StatusRange(200, 299),
+            Assertion::Status(200),
+            Assertion::HeaderPresent("content-type".to_string()),
+            Assertion::HeaderMatches("Content-Type".to_string(), "^application/json".to_string()),
+            Assertion::HeaderPresent("ETag".to_string()),
+            Assertion::JsonEquals("$.user.id".to_string(), "{{id}}".to_string()),
+            Assertion::JsonEquals("$.user.name".to_string(), "weaver".to_string()),
+            Assertion::JsonEquals("$.user.name".to_string(), "\"other\"".to_string()),
+            Assertion::JsonExists("$.user.email".to_string()),
+            Assertion::JsonType("$.tags".to_string(), "array".to_string()),
+            Assertion::BodyContains("weaver".to_string()),
+            Assertion::TimeUnder(100),
+        ],
+        &response,
+        &variables,
+    );
+    let errors: Vec<Option<&str>> = outcomes
+        .iter()
+        .map(|outcome| outcome.error.as_deref())
+        .collect();
+    assert_eq!(
+        errors,
+        vec![
+            None,
+            None,
+            Some("Got 201"),
+            None,
+            None,
+            Some("No header ETag"),
+            None,
+            None,
+            Some("Got \"weaver\""),
+            Some("Not found"),
+            None,
+            None,
+            Some("Took 120 ms"),
+        ]
+    );
+}
@@ -0,0 +1,457 @@
+use eframe::egui::{ComboBox, Grid, TextEdit, Ui};
+use md5::Md5;
+use rand::{distributions::Alphanumeric, Rng};
+use reqwest::RequestBuilder;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::environment::{self, Variables};
+use crate::request::oauth2::OAuth2;
+
+/// Authorization of request,credentials are applied when sending.
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug, Default)]
+pub enum Auth {
+    #[default]
+    None,
+    Basic {
+        username: String,
+        password: String,
+    },
+    Bearer {
+        token: String,
+    },
+    ApiKey {
+        key: String,
+        value: String,
+        location: ApiKeyLocation,
+    },
+    /// HTTP Digest,the challenge is answered in request thread.
+    Digest {
+        username: String,
+        password: String,
+    },
+    /// OAuth 2.0,token is fetched in request thread and sent as bearer token.
+    OAuth2(OAuth2),
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ApiKeyLocation {
+    #[default]
+    Header,
+    Query,
+}
+
+impl Auth {
+    pub fn label(&self) -> &str {
+        match self {
+            Auth::None => "None",
+            Auth::Basic { .. } => "Basic",
+            Auth::Bearer { .. } => "Bearer",
+            Auth::ApiKey { .. } => "API Key",
+            Auth::Digest { .. } => "Digest",
+            Auth::OAuth2(_) => "OAuth 2.0",
+        }
+    }
+
+    /// Types listed in auth select.
+    fn presets() -> [Auth; 6] {
+        [
+            Auth::None,
+            Auth::Basic {
+                username: "".to_string(),
+                password: "".to_string(),
+            },
+            Auth::Bearer {
+                token: "".to_string(),
+            },
+            Auth::ApiKey {
+                key: "".to_string(),
+                value: "".to_string(),
+                location: Default::default(),
+            },
+            Auth::Digest {
+                username: "".to_string(),
+                password: "".to_string(),
+            },
+            Auth::OAuth2(Default::default()),
+        ]
+    }
+
+    pub fn view(&mut self, ui: &mut Ui, variables: &Variables) {
+        ui.horizontal(|ui| {
+            ui.label("Type");
+            ComboBox::from_id_source("auth_type_combo_box")
+                .selected_text(self.label().to_string())
+                .show_ui(ui, |ui| {
+                    for auth in Auth::presets() {
+                        let selected =
+                            std::mem::discriminant(self) == std::mem::discriminant(&auth);
+                        if ui.selectable_label(selected, auth.label()).clicked() && !selected {
+                            *self = auth;
+                        }
+                    }
+                });
+        });
+        ui.add_space(5.0);
+        if let Auth::OAuth2(oauth2) = self {
+            oauth2.view(ui, variables);
+            return;
+        }
+        let width = ui.available_width() / 2.0;
+        Grid::new("auth_grid")
+            .num_columns(2)
+            .show(ui, |ui| match self {
+                Auth::None => {
+                    ui.label("This request does not use any authorization.");
+                    ui.end_row();
+                }
+                Auth::Basic { username, password } | Auth::Digest { username, password } => {
+                    ui.label("Username");
+                    environment::variable_text_edit(ui, username, variables, width);
+                    ui.end_row();
+                    ui.label("Password");
+                    ui.add(
+                        TextEdit::singleline(password)
+                            .password(true)
+                            .desired_width(width),
+                    );
+                    ui.end_row();
+                }
+                Auth::Bearer { token } => {
+                    ui.label("Token");
+                    environment::variable_text_edit(ui, token, variables, width);
+                    ui.end_row();
+                }
+                Auth::ApiKey {
+                    key,
+                    value,
+                    location,
+                } => {
+                    ui.label("Key");
+                    environment::variable_text_edit(ui, key, variables, width);
+                    ui.end_row();
+                    ui.label("Value");
+                    environment::variable_text_edit(ui, value, variables, width);
+                    ui.end_row();
+                    ui.label("Add to");
+                    ui.horizontal(|ui| {
+                        ui.selectable_value(location, ApiKeyLocation::Header, "Header");
+                        ui.selectable_value(location, ApiKeyLocation::Query, "Query");
+                    });
+                    ui.end_row();
+                }
+                Auth::OAuth2(_) => {}
+            });
+    }
+
+    /// All texts which may contain `{{name}}` placeholders.
+    pub fn texts(&self) -> Vec<&String> {
+        match self {
+            Auth::None => vec![],
+            Auth::Basic { username, password } | Auth::Digest { username, password } => {
+                vec![username, password]
+            }
+            Auth::Bearer { token } => vec![token],
+            Auth::ApiKey { key, value, .. } => vec![key, value],
+            Auth::OAuth2(oauth2) => oauth2.texts(),
+        }
+    }
+
+    /// Resolve `{{name}}` placeholders by variables.
+    pub fn resolve(&self, variables: &Variables) -> Self {
+        let mut auth = self.clone();
+        match &mut auth {
+            Auth::None => {}
+            Auth::Basic { username, password } | Auth::Digest { username, password } => {
+                *username = environment::substitute(username, variables);
+                *password = environment::substitute(password, variables);
+            }
+            Auth::Bearer { token } => {
+                *token = environment::substitute(token, variables);
+            }
+            Auth::ApiKey { key, value, .. } => {
+                *key = environment::substitute(key, variables);
+                *value = environment::substitute(value, variables);
+            }
+            Auth::OAuth2(oauth2) => {
+                *oauth2 = oauth2.resolve(variables);
+            }
+        }
+        auth
+    }
+
+    /// Apply credentials to request,digest is applied by `digest_authorization` after challenge,
+    /// OAuth2 should be replaced by bearer token before applying.
+    pub fn apply(&self, builder: RequestBuilder) -> RequestBuilder {
+        match self {
+            Auth::Basic { username, password } => builder.basic_auth(username, Some(password)),
+            Auth::Bearer { token } => builder.bearer_auth(token),
+            Auth::ApiKey {
+                key,
+                value,
+                location: ApiKeyLocation::Header,
+            } => builder.header(key, value),
+            Auth::ApiKey {
+                key,
+                value,
+                location: ApiKeyLocation::Query,
+            } => builder.query(&[(key, value)]),
+            Auth::None | Auth::Digest { .. } | Auth::OAuth2(_) => builder,
+        }
+    }
+}
+
+/// Create value of `Authorization` header to answer a digest challenge from `WWW-Authenticate` header.
+pub fn digest_authorization(
+    challenge: &str,
+    method: &str,
+    url: &Url,
+    username: &str,
+    password: &str,
+) -> Result<String, String> {
+    // Scheme is case-insensitive.
+    let params = match challenge.trim().split_once(char::is_whitespace) {
+        Some((scheme, params)) if scheme.eq_ignore_ascii_case("Digest") => parse_challenge(params),
+        _ => return Err(format!("Not a digest challenge: {}", challenge)),
+    };
+    let param = |name: &str| {
+        params
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    };
+    let realm = param("realm").unwrap_or("");
+    let nonce = match param("nonce") {
+        None => return Err("No nonce in digest challenge".to_string()),
+        Some(nonce) => nonce,
+    };
+    let algorithm = param("algorithm").unwrap_or("MD5");
+    let hash: fn(&str) -> String = match algorithm.to_uppercase().as_str() {
+        "MD5" | "MD5-SESS" => md5_hex,
+        "SHA-256" | "SHA-256-SESS" => sha256_hex,
+        _ => return Err(format!("Unsupported digest algorithm: {}", algorithm)),
+    };
+    // Only `auth` is supported,`auth-int` need hash of body.
+    let qop = param("qop").and_then(|qop| {
+        qop.split(',')
+            .map(|qop| qop.trim())
+            .find(|qop| qop.eq_ignore_ascii_case("auth"))
+    });
+    let cnonce: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(16)
+        .map(char::from)
+        .collect();
+    let uri = match url.query() {
+        None => url.path().to_string(),
+        Some(query) => format!("{}?{}", url.path(), query),
+    };
+
+    let response = digest_response(
+        hash, algorithm, username, password, realm, nonce, &cnonce, qop, method, &uri,
+    );
+    let mut authorization = format!(
+        r#"Digest username="{}", realm="{}", nonce="{}", uri="{}", algorithm={}, response="{}""#,
+        username, realm, nonce, uri, algorithm, response
+    );
+    if let Some(qop) = qop {
+        authorization += &format!(r#", qop={}, nc=00000001, cnonce="{}""#, qop, cnonce);
+    }
+    if let Some(opaque) = param("opaque") {
+        authorization += &format!(r#", opaque="{}""#, opaque);
+    }
+    Ok(authorization)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn digest_response(
+    hash: fn(&str) -> String,
+    algorithm: &str,
+    username: &str,
+    password: &str,
+    realm: &str,
+    nonce: &str,
+    cnonce: &str,
+    qop: Option<&str>,
+    method: &str,
+    uri: &str,
+) -> String {
+    let mut ha1 = hash(&format!("{}:{}:{}", username, realm, password));
+    if algorithm.to_uppercase().ends_with("-SESS") {
+        ha1 = hash(&format!("{}:{}:{}", ha1, nonce, cnonce));
+    }
+    let ha2 = hash(&format!("{}:{}", method, uri));
+    match qop {
+        None => hash(&format!("{}:{}:{}", ha1, nonce, ha2)),
+        Some(qop) => hash(&format!(
+            "{}:{}:00000001:{}:{}:{}",
+            ha1, nonce, cnonce, qop, ha2
+        )),
+    }
+}
+
+/// Digest challenge in values of `WWW-Authenticate`,a value may have several challenges separated by `,`.
+pub fn digest_challenge<'a>(values: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    for value in values {
+        let mut digest: Option<Vec<&str>> = None;
+        for part in split_quoted(value, ',') {
+            let part = part.trim();
+            if part.is_empty() {
+                continue;
+            }
+            // Challenge starts with scheme,which isn't followed by `=` like params.
+            let scheme = part
+                .split(|c: char| c.is_whitespace() || c == '=')
+                .next()
+                .unwrap_or_default();
+            let rest = part[scheme.len()..].trim();
+            if !rest.starts_with('=') {
+                if digest.is_some() {
+                    break;
+                }
+                if scheme.eq_ignore_ascii_case("Digest") {
+                    digest = Some(vec![rest]);
+                }
+            } else if let Some(params) = &mut digest {
+                params.push(part);
+            }
+        }
+        if let Some(params) = digest {
+            return Some(format!("Digest {}", params.join(", ")));
+        }
+    }
+    None
+}
+
+/// Split text by separator which isn't in quotes.
+fn split_quoted(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut start = 0;
+    let mut quoted = false;
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            c if c == separator && !quoted => {
+                parts.push(&text[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+/// Parse `key=value, key="value"` pairs of challenge.
+fn parse_challenge(params: &str) -> Vec<(String, String)> {
+    let mut result = vec![];
+    let mut chars = params.chars().peekable();
+    loop {
+        while matches!(chars.peek(), Some(c) if *c == ',' || c.is_whitespace()) {
+            chars.next();
+        }
+        let key: String = std::iter::from_fn(|| chars.next_if(|c| *c != '=')).collect();
+        if key.is_empty() || chars.next().is_none() {
+            break;
+        }
+        let mut value = String::new();
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' => value.extend(chars.next()),
+                    '"' => break,
+                    c => value.push(c),
+                }
+            }
+        } else {
+            value = std::iter::from_fn(|| chars.next_if(|c| *c != ',')).collect();
+        }
+        result.push((key.trim().to_string(), value.trim().to_string()));
+    }
+    result
+}
+
+fn md5_hex(text: &str) -> String {
+    hex::encode(Md5::digest(text.as_bytes()))
+}
+
+fn sha256_hex(text: &str) -> String {
+    hex::encode(Sha256::digest(text.as_bytes()))
+}
+
+#[test]
+fn test_digest() {
+    // Example of RFC 2617
+    assert_eq!(
+        digest_response(
+            md5_hex,
+            "MD5",
+            "Mufasa",
+            "Circle Of Life",
+            "testrealm@host.com",
+            "dcd98b7102dd2f0e8b11d0f600bfb0c093",
+            "0a4f113b",
+            Some("auth"),
+            "GET",
+            "/dir/index.html",
+        ),
+        "6629fae49393a05397450978507c4ef1"
+    );
+    // Example of RFC 7616
+    assert_eq!(
+        digest_response(
+            sha256_hex,
+            "SHA-256",
+            "Mufasa",
+            "Circle of Life",
+            "http-auth@example.org",
+            "7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v",
+            "f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ",
+            Some("auth"),
+            "GET",
+            "/dir/index.html",
+        ),
+        "753927fa0e85d155564e2e272a28d1802ca10daf4496794697cf8db5856cb6c1"
+    );
+
+    let params = parse_challenge(
+        r#" realm="http-auth@example.org", qop="auth, auth-int", algorithm=SHA-256, nonce="7ypf", opaque="FQhe\"x""#,
+    );
+    assert_eq!(
+        params,
+        vec![
+            ("realm".to_string(), "http-auth@example.org".to_string()),
+            ("qop".to_string(), "auth, auth-int".to_string()),
+            ("algorithm".to_string(), "SHA-256".to_string()),
+            ("nonce".to_string(), "7ypf".to_string()),
+            ("opaque".to_string(), "FQhe\"x".to_string()),
+        ]
+    );
+
+    // Scheme is case-insensitive,challenges may share a header.
+    assert_eq!(
+        digest_challenge([r#"digest realm="r", nonce="n""#]).as_deref(),
+        Some(r#"Digest realm="r", nonce="n""#)
+    );
+    assert_eq!(
+        digest_challenge([
+            r#"Basic realm="a, b", DIGEST realm="r",nonce="n", qop="auth,auth-int", Bearer x="#
+        ])
+        .as_deref(),
+        Some(r#"Digest realm="r", nonce="n", qop="auth,auth-int""#)
+    );
+    assert_eq!(
+        digest_challenge([r#"Basic realm="x""#, r#"Digest nonce="n""#]).as_deref(),
+        Some(r#"Digest nonce="n""#)
+    );
+    assert_eq!(digest_challenge([r#"Basic realm="x""#]), None);
+    let url = Url::parse("http://localhost/a?b=1").unwrap();
+    let authorization =
+        digest_authorization(r#"digest realm="r", nonce="n""#, "GET", &url, "u", "p").unwrap();
+    assert!(authorization.contains(r#"uri="/a?b=1""#));
+}
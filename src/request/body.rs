@@ -0,0 +1,198 @@
+use std::fmt::Write;
+use std::sync::{Arc, OnceLock};
+
+use eframe::egui::{ColorImage, Id, TextEdit, TextStyle, TextureHandle, Ui, Vec2};
+use encoding_rs::{Encoding, UTF_8};
+
+use crate::color;
+use crate::style;
+
+/// Bytes more than this are left out of hex view.
+const MAX_HEX: usize = 64 * 1024;
+
+/// How body of response is shown,which is told by `Content-Type` and bytes.
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Default)]
+pub enum BodyKind {
+    #[default]
+    Text,
+    Image,
+    Binary,
+}
+
+/// Decoded image of response,its texture is loaded when it's shown first.
+#[derive(Clone)]
+pub struct Image {
+    color_image: Arc<ColorImage>,
+    texture: Arc<OnceLock<TextureHandle>>,
+}
+
+/// Kind of body and its text,which is decoded by charset of content type.
+/// Body which isn't text has empty text.
+pub fn decode(content_type: Option<&str>, bytes: &[u8]) -> (BodyKind, String) {
+    let content_type = content_type.unwrap_or_default().to_ascii_lowercase();
+    let mime = content_type.split(';').next().unwrap_or_default().trim();
+    let charset = content_type.split(';').skip(1).find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        (key.trim() == "charset").then(|| value.trim().trim_matches('"').to_string())
+    });
+    if mime.starts_with("image/") && mime != "image/svg+xml" {
+        return (BodyKind::Image, "".to_string());
+    }
+    let textual = mime.starts_with("text/")
+        || mime.ends_with("json")
+        || mime.ends_with("xml")
+        || mime.ends_with("javascript")
+        || mime == "application/x-www-form-urlencoded";
+    match charset.and_then(|charset| Encoding::for_label(charset.as_bytes())) {
+        Some(encoding) if textual => {
+            let (text, _, _) = encoding.decode(bytes);
+            (BodyKind::Text, text.to_string())
+        }
+        // Text of unknown charset is taken as utf-8.
+        _ if textual => (BodyKind::Text, UTF_8.decode(bytes).0.to_string()),
+        _ => match std::str::from_utf8(bytes) {
+            Ok(text) if !text.contains('\0') => (BodyKind::Text, text.to_string()),
+            _ => (BodyKind::Binary, "".to_string()),
+        },
+    }
+}
+
+impl Image {
+    pub fn load(bytes: &[u8]) -> Result<Self, String> {
+        Ok(Self {
+            color_image: Arc::new(
+                style::load_image_from_memory(bytes).map_err(|err| err.to_string())?,
+            ),
+            texture: Default::default(),
+        })
+    }
+
+    pub fn size(&self) -> [usize; 2] {
+        self.color_image.size
+    }
+
+    pub fn view(&self, ui: &mut Ui) {
+        let texture = self.texture.get_or_init(|| {
+            ui.ctx()
+                .load_texture("response_image", (*self.color_image).clone())
+        });
+        let [width, height] = self.size();
+        ui.colored_label(color::GRAY, format!("{} × {}", width, height));
+        // Large image is scaled down to fit the panel.
+        let mut size = Vec2::new(width as f32, height as f32);
+        if size.x > ui.available_width() {
+            size *= ui.available_width() / size.x;
+        }
+        ui.image(texture.id(), size);
+    }
+}
+
+/// Hex dump with offset and printable characters,like `hexdump -C`.
+pub fn hex_dump(bytes: &[u8]) -> String {
+    let mut dump = String::new();
+    for (i, line) in bytes[..bytes.len().min(MAX_HEX)].chunks(16).enumerate() {
+        let _ = write!(dump, "{:08x}  ", i * 16);
+        for j in 0..16 {
+            match line.get(j) {
+                Some(byte) => {
+                    let _ = write!(dump, "{:02x} ", byte);
+                }
+                None => dump.push_str("   "),
+            }
+            if j == 7 {
+                dump.push(' ');
+            }
+        }
+        dump.push_str(" |");
+        dump.extend(line.iter().map(|&byte| match byte {
+            0x20..=0x7e => byte as char,
+            _ => '.',
+        }));
+        dump.push_str("|\n");
+    }
+    if bytes.len() > MAX_HEX {
+        let _ = writeln!(dump, "… first {} of {} bytes", MAX_HEX, bytes.len());
+    }
+    dump
+}
+
+pub fn hex_view(ui: &mut Ui, bytes: &[u8]) {
+    let dump = hex_dump(bytes);
+    let mut dump = dump.as_str();
+    ui.add(
+        TextEdit::multiline(&mut dump)
+            .font(TextStyle::Monospace)
+            .desired_width(f32::INFINITY),
+    );
+}
+
+/// Save bytes of response to the picked file,file name is suggested by headers or url.
+pub fn save_button(ui: &mut Ui, bytes: &[u8], headers: &[(String, String)], url: &str) {
+    let id = Id::new("response_saved");
+    if ui.button("Save to file").clicked() {
+        let mut dialog = rfd::FileDialog::new();
+        if let Some(name) = file_name(headers, url) {
+            dialog = dialog.set_file_name(&name);
+        }
+        if let Some(path) = dialog.save_file() {
+            let message = match std::fs::write(&path, bytes) {
+                Ok(_) => format!("Saved to {}", path.display()),
+                Err(err) => format!("Failed to save: {}", err),
+            };
+            ui.data().insert_temp(id, message);
+        }
+    }
+    let message = ui.data().get_temp::<String>(id);
+    if let Some(message) = message {
+        ui.colored_label(color::GRAY, message);
+    }
+}
+
+fn file_name(headers: &[(String, String)], url: &str) -> Option<String> {
+    let disposition = headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case("content-disposition"))
+        .and_then(|(_, value)| {
+            value.split(';').find_map(|param| {
+                let (key, value) = param.split_once('=')?;
+                (key.trim() == "filename").then(|| value.trim().trim_matches('"').to_string())
+            })
+        });
+    disposition.or_else(|| {
+        let url = reqwest::Url::parse(url).ok()?;
+        let name = url.path_segments()?.next_back()?;
+        (!name.is_empty()).then(|| name.to_string())
+    })
+}
+
+#[test]
+fn test_decode() {
+    let gbk = [0xc4, 0xe3, 0xba, 0xc3];
+    assert_eq!(
+        decode(Some("text/plain; charset=GBK"), &gbk),
+        (BodyKind::Text, "你好".to_string())
+    );
+    assert_eq!(
+        decode(
+            Some("text/html; charset=\"ISO-8859-1\""),
+            &[0x63, 0x61, 0x66, 0xe9]
+        ),
+        (BodyKind::Text, "café".to_string())
+    );
+    assert_eq!(
+        decode(Some("application/json"), "{\"a\":\"é\"}".as_bytes()),
+        (BodyKind::Text, "{\"a\":\"é\"}".to_string())
+    );
+    assert_eq!(decode(Some("image/png"), &[0x89, b'P']).0, BodyKind::Image);
+    assert_eq!(decode(Some("image/svg+xml"), b"<svg/>").0, BodyKind::Text);
+    assert_eq!(decode(None, b"plain").0, BodyKind::Text);
+    assert_eq!(
+        decode(Some("application/octet-stream"), &[0x1f, 0x8b, 0x08, 0x00]).0,
+        BodyKind::Binary
+    );
+    assert_eq!(
+        hex_dump(b"0123456789abcdefXY"),
+        "00000000  30 31 32 33 34 35 36 37  38 39 61 62 63 64 65 66  |0123456789abcdef|\n\
+         00000010  58 59                                             |XY|\n"
+    );
+}
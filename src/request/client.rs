@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use crate::cookie;
+use crate::request::proxy::Proxy;
+use crate::request::tls::ClientIdentity;
+
+/// Clients are kept to reuse connections,TLS sessions and cookies between requests.
+static CLIENTS: OnceLock<Mutex<HashMap<ClientOptions, reqwest::Client>>> = OnceLock::new();
+
+/// Options which need a client of their own.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ClientOptions {
+    /// Name of environment,each has its own cookies.
+    pub session: String,
+    pub cookies: bool,
+    pub connect_timeout: Option<Duration>,
+    /// Effective proxy settings.
+    pub proxy: Proxy,
+    pub insecure: bool,
+    pub ca_files: Vec<PathBuf>,
+    /// Identity for host of request.
+    pub identity: Option<ClientIdentity>,
+}
+
+/// Shared client with the options,it's created at the first time.
+pub fn client(options: &ClientOptions) -> Result<reqwest::Client, String> {
+    let mut clients = CLIENTS.get_or_init(Default::default).lock().unwrap();
+    if let Some(client) = clients.get(options) {
+        return Ok(client.clone());
+    }
+    // Redirects are followed by request itself to record them.
+    // Certificate of server is shown in response.
+    let mut builder = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .tls_info(true);
+    if options.cookies {
+        builder = builder.cookie_provider(cookie::jar(&options.session));
+    }
+    if let Some(connect_timeout) = options.connect_timeout {
+        builder = builder.connect_timeout(connect_timeout);
+    }
+    if options.insecure {
+        builder = builder
+            .danger_accept_invalid_certs(true)
+            .danger_accept_invalid_hostnames(true);
+    }
+    // Path is empty if file isn't picked.
+    for ca_file in options
+        .ca_files
+        .iter()
+        .filter(|path| !path.as_os_str().is_empty())
+    {
+        let pem =
+            std::fs::read(ca_file).map_err(|err| format!("{}: {}", ca_file.display(), err))?;
+        let certificates = reqwest::Certificate::from_pem_bundle(&pem)
+            .map_err(|err| format!("Invalid CA file {}: {}", ca_file.display(), err))?;
+        for certificate in certificates {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+    if let Some(identity) = &options.identity {
+        builder = builder.identity(identity.load()?);
+    }
+    options.proxy.validate()?;
+    let proxy = options.proxy.clone();
+    builder = builder.proxy(reqwest::Proxy::custom(move |url| proxy.select(url)));
+    let client = builder.build().map_err(|err| err.to_string())?;
+    clients.insert(options.clone(), client.clone());
+    Ok(client)
+}
@@ -0,0 +1,292 @@
+use eframe::egui::{ComboBox, Grid, TextEdit, Ui};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::color;
+use crate::environment::{self, Variables};
+use crate::request::body::BodyKind;
+use crate::request::json;
+use crate::request::response::Response;
+
+/// Value taken from response into a variable of active environment.
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct Extraction {
+    pub variable: String,
+    pub source: Source,
+}
+
+/// Where value is taken from,text is resolved by variables before extracting.
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub enum Source {
+    JsonPath(String),
+    Header(String),
+    /// Regex over body,the first group is taken if there is one,otherwise the whole match.
+    Regex(String),
+    /// Cookie set by response or redirects before it.
+    Cookie(String),
+}
+
+/// Result of an extraction,which is shown after sending.
+#[derive(Clone, Debug)]
+pub struct Extracted {
+    pub variable: String,
+    pub source: String,
+    pub value: Result<String, String>,
+}
+
+impl Source {
+    /// Sources listed in kind select.
+    fn presets() -> [Source; 4] {
+        [
+            Source::JsonPath("$.".to_string()),
+            Source::Header("".to_string()),
+            Source::Regex("".to_string()),
+            Source::Cookie("".to_string()),
+        ]
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Source::JsonPath(_) => "Json path",
+            Source::Header(_) => "Header",
+            Source::Regex(_) => "Body regex",
+            Source::Cookie(_) => "Cookie",
+        }
+    }
+
+    fn text(&mut self) -> (&mut String, &'static str) {
+        match self {
+            Source::JsonPath(path) => (path, "$.path"),
+            Source::Header(name) => (name, "Header"),
+            Source::Regex(pattern) => (pattern, "Regex"),
+            Source::Cookie(name) => (name, "Cookie"),
+        }
+    }
+
+    /// Readable description,such as `header Location`.
+    pub fn name(&self) -> String {
+        match self {
+            Source::JsonPath(path) => path.to_string(),
+            Source::Header(name) => format!("header {}", name),
+            Source::Regex(pattern) => format!("body /{}/", pattern),
+            Source::Cookie(name) => format!("cookie {}", name),
+        }
+    }
+
+    pub fn extract(&self, response: &Response, variables: &Variables) -> Result<String, String> {
+        let resolve = |text: &String| environment::substitute(text, variables);
+        match self {
+            Source::JsonPath(path) => match &response.json {
+                None => Err("Body isn't json".to_string()),
+                Some(value) => match json::select(value, &resolve(path))? {
+                    // String is taken without quotes.
+                    Some(Value::String(text)) => Ok(text.clone()),
+                    Some(value) => Ok(value.to_string()),
+                    None => Err("Not found".to_string()),
+                },
+            },
+            Source::Header(name) => {
+                let name = resolve(name);
+                response
+                    .headers
+                    .iter()
+                    .find(|(key, _)| key.eq_ignore_ascii_case(&name))
+                    .map(|(_, value)| value.clone())
+                    .ok_or_else(|| format!("No header {}", name))
+            }
+            Source::Regex(pattern) => {
+                if response.kind != BodyKind::Text {
+                    return Err("Body isn't text".to_string());
+                }
+                let regex = Regex::new(&resolve(pattern)).map_err(|err| err.to_string())?;
+                let captures = regex
+                    .captures(&response.body)
+                    .ok_or_else(|| "Not matched".to_string())?;
+                let found = captures.get(1).or_else(|| captures.get(0));
+                Ok(found
+                    .map(|found| found.as_str())
+                    .unwrap_or_default()
+                    .to_string())
+            }
+            Source::Cookie(name) => {
+                let name = resolve(name);
+                // The final response goes first,then redirects from the last one.
+                std::iter::once(&response.headers)
+                    .chain(response.redirects.iter().rev().map(|hop| &hop.headers))
+                    .flat_map(|headers| headers.iter())
+                    .filter(|(key, _)| key.eq_ignore_ascii_case("set-cookie"))
+                    .filter_map(|(_, value)| ::cookie::Cookie::parse(value.as_str()).ok())
+                    .find(|cookie| cookie.name() == name)
+                    .map(|cookie| cookie.value().to_string())
+                    .ok_or_else(|| format!("No cookie {}", name))
+            }
+        }
+    }
+}
+
+/// Run all extractions against response,rules without variable are skipped.
+pub fn extract(
+    extractions: &[Extraction],
+    response: &Response,
+    variables: &Variables,
+) -> Vec<Extracted> {
+    extractions
+        .iter()
+        .filter(|extraction| !extraction.variable.trim().is_empty())
+        .map(|extraction| Extracted {
+            variable: extraction.variable.trim().to_string(),
+            source: extraction.source.name(),
+            value: extraction.source.extract(response, variables),
+        })
+        .collect()
+}
+
+/// Editor of extractions.
+pub fn view(ui: &mut Ui, extractions: &mut Vec<Extraction>) {
+    let mut remove = None;
+    Grid::new("extractions_grid").num_columns(4).show(ui, |ui| {
+        for (i, extraction) in extractions.iter_mut().enumerate() {
+            ui.add(
+                TextEdit::singleline(&mut extraction.variable)
+                    .hint_text("Variable")
+                    .desired_width(150.0),
+            );
+            ComboBox::from_id_source(format!("extraction_kind_{}", i))
+                .selected_text(extraction.source.kind())
+                .show_ui(ui, |ui| {
+                    for preset in Source::presets() {
+                        let kind = preset.kind();
+                        if ui
+                            .selectable_label(extraction.source.kind() == kind, kind)
+                            .clicked()
+                            && extraction.source.kind() != kind
+                        {
+                            extraction.source = preset;
+                        }
+                    }
+                });
+            let (text, hint) = extraction.source.text();
+            ui.add(
+                TextEdit::singleline(text)
+                    .hint_text(hint)
+                    .desired_width(240.0),
+            );
+            if ui.button("🗑").clicked() {
+                remove = Some(i);
+            }
+            ui.end_row();
+        }
+    });
+    if let Some(i) = remove {
+        extractions.remove(i);
+    }
+    if ui.button("Add extraction").clicked() {
+        extractions.push(Extraction {
+            variable: "".to_string(),
+            source: Source::JsonPath("$.".to_string()),
+        });
+    }
+}
+
+/// Extracted values and their sources,`stored` tells whether there is an environment to keep them.
+pub fn extracted_view(ui: &mut Ui, extracted: &[Extracted], stored: bool) {
+    ui.label("Extracted");
+    for extracted in extracted {
+        match &extracted.value {
+            Ok(value) => ui.colored_label(
+                color::SEA_GREEN,
+                format!(
+                    "✔ {} = {} (from {})",
+                    extracted.variable, value, extracted.source
+                ),
+            ),
+            Err(err) => ui.colored_label(
+                color::CRIMSON,
+                format!(
+                    "✘ {} (from {}): {}",
+                    extracted.variable, extracted.source, err
+                ),
+            ),
+        };
+    }
+    if !stored {
+        ui.colored_label(
+            color::DARK_ORANGE,
+            "No active environment,values aren't stored",
+        );
+    }
+}
+
+#[test]
+fn test_extract() {
+    let body = r#"{"data":{"token":"abc","id":7}} csrf="x-123""#;
+    let response = Response {
+        headers: vec![
+            ("Location".to_string(), "/users/7".to_string()),
+            (
+                "Set-Cookie".to_string(),
+                "session=new; Path=/; HttpOnly".to_string(),
+            ),
+        ],
+        redirects: vec![crate::request::redirect::Hop {
+            method: "POST".to_string(),
+            url: "http://localhost/login".to_string(),
+            status: reqwest::StatusCode::FOUND,
+            location: "/".to_string(),
+            headers: vec![
+                ("set-cookie".to_string(), "session=old".to_string()),
+                (
+                    "set-cookie".to_string(),
+                    "remember=1; Max-Age=60".to_string(),
+                ),
+            ],
+            elapsed: Default::default(),
+        }],
+        json: Some(serde_json::json!({"data":{"token":"abc","id":7}})),
+        body: body.to_string(),
+        ..Response::default()
+    };
+    let rule = |variable: &str, source: Source| Extraction {
+        variable: variable.to_string(),
+        source,
+    };
+    let variables = Variables::from([("field".to_string(), "token".to_string())]);
+    let extracted = extract(
+        &[
+            rule("token", Source::JsonPath("$.data.{{field}}".to_string())),
+            rule("id", Source::JsonPath("$.data.id".to_string())),
+            rule("missing", Source::JsonPath("$.data.name".to_string())),
+            rule("location", Source::Header("location".to_string())),
+            rule("csrf", Source::Regex(r#"csrf="([^"]+)""#.to_string())),
+            rule("whole", Source::Regex("x-\\d+".to_string())),
+            rule("session", Source::Cookie("session".to_string())),
+            rule("remember", Source::Cookie("remember".to_string())),
+            rule(" ", Source::Header("Location".to_string())),
+        ],
+        &response,
+        &variables,
+    );
+    let values: Vec<(&str, Result<&str, &str>)> = extracted
+        .iter()
+        .map(|extracted| {
+            (
+                extracted.variable.as_str(),
+                extracted.value.as_deref().map_err(String::as_str),
+            )
+        })
+        .collect();
+    assert_eq!(
+        values,
+        vec![
+            ("token", Ok("abc")),
+            ("id", Ok("7")),
+            ("missing", Err("Not found")),
+            ("location", Ok("/users/7")),
+            ("csrf", Ok("x-123")),
+            ("whole", Ok("x-123")),
+            ("session", Ok("new")),
+            ("remember", Ok("1")),
+        ]
+    );
+}
@@ -0,0 +1,845 @@
+use std::ops::Add;
+
+use eframe::egui::text::CCursor;
+use eframe::egui::text_edit::{CCursorRange, TextEditState};
+use eframe::egui::{
+    Button, CollapsingHeader, FontSelection, Grid, Id, Layout, Rect, RichText, ScrollArea,
+    TextEdit, TextStyle, Ui, Vec2, WidgetText,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::environment::{self, Variables};
+use crate::history::{Entry, Snapshot};
+use crate::request::auth::Auth;
+use crate::request::http::{get_request_promise, get_uuid, RequestData, RequestResult, Sending};
+use crate::request::json;
+use crate::request::response::{BodyView, ResponseTab};
+use crate::request::{Request, RequestContext};
+use crate::{color, components};
+
+/// Fields suggested at most.
+const MAX_SUGGESTIONS: usize = 30;
+
+/// Standard introspection query,`ofType` is nested deep enough for types like `[[Int!]!]!`.
+const INTROSPECTION_QUERY: &str = "query IntrospectionQuery { __schema { \
+    queryType { name } mutationType { name } subscriptionType { name } \
+    types { kind name description \
+    fields(includeDeprecated: true) { name description args { name description type { ...TypeRef } } type { ...TypeRef } } \
+    inputFields { name description type { ...TypeRef } } \
+    enumValues(includeDeprecated: true) { name } } } } \
+    fragment TypeRef on __Type { kind name ofType { kind name ofType { kind name ofType { kind name \
+    ofType { kind name ofType { kind name ofType { kind name } } } } } } }";
+
+#[derive(Deserialize, Serialize)]
+pub struct GraphQL {
+    id: String,
+    name: String,
+    url: String,
+    method: Method,
+    header: Vec<(String, String)>,
+    query: String,
+    /// Json object of operation variables.
+    variables_json: String,
+    operation_name: String,
+    #[serde(default)]
+    auth: Auth,
+    /// Schema fetched by introspection.
+    #[serde(default)]
+    schema: Option<Schema>,
+    #[serde(skip)]
+    tab: Tab,
+    #[serde(skip)]
+    response_tab: ResponseTab,
+    #[serde(skip)]
+    body_view: BodyView,
+    #[serde(skip)]
+    result: Option<RequestResult>,
+    #[serde(skip)]
+    state: Option<Sending>,
+    #[serde(skip)]
+    snapshot: Option<Snapshot>,
+    #[serde(skip)]
+    introspection: Option<Sending>,
+    #[serde(skip)]
+    schema_error: Option<String>,
+    /// Type shown in type explorer.
+    #[serde(skip)]
+    explored: Option<String>,
+    #[serde(skip)]
+    type_filter: String,
+}
+
+#[derive(Deserialize, Serialize, Eq, PartialEq, Clone, Copy, Default)]
+enum Method {
+    #[default]
+    Post,
+    /// Operation is sent by query string.
+    Get,
+}
+
+#[derive(Eq, PartialEq, Clone, Copy, Default)]
+enum Tab {
+    #[default]
+    Query,
+    Variables,
+    Header,
+    Auth,
+}
+
+/// Simplified result of introspection.
+#[derive(Deserialize, Serialize, Default)]
+pub struct Schema {
+    query_type: Option<String>,
+    mutation_type: Option<String>,
+    subscription_type: Option<String>,
+    types: Vec<Type>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct Type {
+    kind: String,
+    name: String,
+    description: String,
+    /// Fields of object and interface,or input fields of input object.
+    fields: Vec<Field>,
+    enum_values: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct Field {
+    name: String,
+    description: String,
+    /// Such as `[User!]!`.
+    type_ref: String,
+    args: Vec<Field>,
+}
+
+impl Default for GraphQL {
+    fn default() -> Self {
+        Self {
+            id: get_uuid(),
+            name: "New graphql request".to_string(),
+            url: "".to_string(),
+            method: Default::default(),
+            header: vec![],
+            query: "".to_string(),
+            variables_json: "".to_string(),
+            operation_name: "".to_string(),
+            auth: Default::default(),
+            schema: None,
+            tab: Default::default(),
+            response_tab: Default::default(),
+            body_view: Default::default(),
+            result: None,
+            state: None,
+            snapshot: None,
+            introspection: None,
+            schema_error: None,
+            explored: None,
+            type_filter: "".to_string(),
+        }
+    }
+}
+
+impl Request for GraphQL {
+    fn request_name(&self) -> &str {
+        if self.name.is_empty() {
+            return "GraphQL";
+        }
+        self.name.as_str()
+    }
+
+    fn badge(&self) -> Option<&str> {
+        Some("GQL")
+    }
+
+    fn view(&mut self, ui: &mut Ui, context: &mut RequestContext) {
+        self.poll(context);
+        let variables = context.variables;
+        let (_, row_height) = crate::style::get_row_height(ui);
+        ui.add_space(10.0);
+        ui.add(
+            TextEdit::singleline(&mut self.name)
+                .margin(Vec2::new(5.0, 5.0))
+                .desired_width(f32::INFINITY),
+        );
+        ui.add_space(10.0);
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.method, Method::Post, "POST");
+            ui.selectable_value(&mut self.method, Method::Get, "GET");
+            let mut layouter = |ui: &Ui, string: &str, wrap_width: f32| {
+                let font_id = FontSelection::Style(TextStyle::Button).resolve(ui.style());
+                ui.fonts().layout_job(environment::highlight(
+                    string, variables, font_id, wrap_width,
+                ))
+            };
+            ui.add(
+                TextEdit::singleline(&mut self.url)
+                    .hint_text("http://localhost:4000/graphql")
+                    .desired_width(ui.available_width() - 210.0)
+                    .layouter(&mut layouter),
+            );
+            let introspect = Button::new(if self.introspection.is_some() {
+                "Introspecting…"
+            } else {
+                "Introspect"
+            });
+            if components::widget_with_size(ui, Vec2::new(100.0, row_height + 4.0), introspect)
+                .clicked()
+                && self.introspection.is_none()
+            {
+                self.introspect(context);
+            }
+            self.send_button(ui, row_height, context);
+        });
+        if let Some(err) = &self.schema_error {
+            ui.colored_label(color::CRIMSON, format!("Introspection failed: {}", err));
+        }
+        if let Some(Err(err)) = &self.result {
+            ui.add_space(5.0);
+            ui.colored_label(color::CRIMSON, err.to_string());
+        }
+        ui.add_space(10.0);
+        CollapsingHeader::new("Request")
+            .default_open(true)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.tab, Tab::Query, "QUERY");
+                    ui.selectable_value(&mut self.tab, Tab::Variables, "VARIABLES");
+                    ui.selectable_value(&mut self.tab, Tab::Header, "HEADER");
+                    ui.selectable_value(&mut self.tab, Tab::Auth, "AUTH");
+                });
+                ui.add_space(5.0);
+                match self.tab {
+                    Tab::Query => self.query_view(ui),
+                    Tab::Variables => self.variables_view(ui),
+                    Tab::Header => self.header_view(ui, variables, row_height),
+                    Tab::Auth => self.auth.view(ui, variables),
+                }
+            });
+        if let Some(schema) = &self.schema {
+            ui.add_space(10.0);
+            CollapsingHeader::new("Schema")
+                .default_open(false)
+                .show(ui, |ui| {
+                    explorer_view(ui, schema, &mut self.explored, &mut self.type_filter)
+                });
+        }
+        ui.add_space(10.0);
+        CollapsingHeader::new("Response")
+            .default_open(true)
+            .show(ui, |ui| {
+                if let Some(Ok(response)) = &self.result {
+                    // Errors of GraphQL come with status 200.
+                    let errors = response
+                        .json
+                        .as_ref()
+                        .and_then(|json| json.get("errors"))
+                        .and_then(|errors| errors.as_array())
+                        .map_or(0, |errors| errors.len());
+                    if errors > 0 {
+                        ui.colored_label(color::CRIMSON, format!("{} errors", errors));
+                    }
+                    response.view(ui, &mut self.response_tab, &mut self.body_view);
+                }
+            });
+    }
+
+    fn get_id(&self) -> String {
+        self.id.to_string()
+    }
+}
+
+impl GraphQL {
+    fn query_view(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Operation name");
+            ui.add(
+                TextEdit::singleline(&mut self.operation_name)
+                    .hint_text("optional")
+                    .desired_width(200.0),
+            );
+        });
+        ui.add_space(5.0);
+        let output = TextEdit::multiline(&mut self.query)
+            .id_source("graphql_query")
+            .code_editor()
+            .desired_rows(10)
+            .desired_width(f32::INFINITY)
+            .hint_text("query { ... }")
+            .show(ui);
+        let schema = match &self.schema {
+            None => return,
+            Some(schema) => schema,
+        };
+        let id = output.response.id;
+        // State keeps cursor after focus moves to a suggestion.
+        let cursor = TextEditState::load(ui.ctx(), id)
+            .and_then(|state| state.ccursor_range())
+            .map(|range| range.primary.index);
+        let cursor = match cursor {
+            None => return,
+            Some(cursor) => cursor,
+        };
+        let offset = self
+            .query
+            .char_indices()
+            .nth(cursor)
+            .map_or(self.query.len(), |(offset, _)| offset);
+        let (typ, prefix) = match completion(schema, &self.query[..offset]) {
+            None => return,
+            Some(completion) => completion,
+        };
+        let suggestions: Vec<&Field> = typ
+            .fields
+            .iter()
+            .filter(|field| field.name.starts_with(prefix) && field.name != prefix)
+            .take(MAX_SUGGESTIONS)
+            .collect();
+        if suggestions.is_empty() {
+            return;
+        }
+        let mut chosen = None;
+        ui.horizontal_wrapped(|ui| {
+            ui.colored_label(color::GRAY, format!("Fields of {}:", typ.name));
+            for field in suggestions {
+                if ui
+                    .small_button(&field.name)
+                    .on_hover_text(format!("{}: {}", field.name, field.type_ref))
+                    .clicked()
+                {
+                    chosen = Some(field.name[prefix.len()..].to_string());
+                }
+            }
+        });
+        if let Some(rest) = chosen {
+            self.query.insert_str(offset, &rest);
+            let mut state = TextEditState::load(ui.ctx(), id).unwrap_or_default();
+            let cursor = CCursor::new(cursor + rest.chars().count());
+            state.set_ccursor_range(Some(CCursorRange::one(cursor)));
+            state.store(ui.ctx(), id);
+            ui.memory().request_focus(id);
+        }
+    }
+
+    fn variables_view(&mut self, ui: &mut Ui) {
+        if ui.button("Beautify").clicked() {
+            if let Ok(text) = json::beautify(&self.variables_json) {
+                self.variables_json = text;
+            }
+        }
+        ui.add_space(5.0);
+        let mut layouter = |ui: &Ui, string: &str, wrap_width: f32| {
+            let font_id = FontSelection::default().resolve(ui.style());
+            ui.fonts()
+                .layout_job(json::highlight(string, font_id, wrap_width))
+        };
+        ui.add(
+            TextEdit::multiline(&mut self.variables_json)
+                .hint_text("{\"id\": 1}")
+                .desired_rows(6)
+                .desired_width(f32::INFINITY)
+                .layouter(&mut layouter),
+        );
+    }
+
+    fn header_view(&mut self, ui: &mut Ui, variables: &Variables, row_height: f32) {
+        let col_width = (ui.available_width() - row_height * 2.0) / 2.0;
+        let mut label = 0;
+        self.header.retain_mut(|(key, value)| {
+            label += 1;
+            !ui.horizontal(|ui| {
+                environment::variable_text_edit(ui, key, variables, col_width);
+                environment::variable_text_edit(ui, value, variables, col_width);
+                let next_pos = ui.next_widget_position();
+                let clear_btn_rect =
+                    Rect::from_min_max(next_pos, next_pos.add(Vec2::splat(row_height / 1.5)));
+                ui.add_space(row_height);
+                components::close_button(
+                    ui,
+                    clear_btn_rect,
+                    Id::new(label.to_string() + "remove_graphql_header_btn"),
+                )
+            })
+            .inner
+            .clicked()
+        });
+        ui.add_space(5.0);
+        ui.vertical_centered(|ui| {
+            ui.style_mut().visuals.widgets.hovered.expansion = 2.0;
+            let next_pos = ui.next_widget_position();
+            let add_btn_rect =
+                Rect::from_min_max(next_pos, next_pos.add(Vec2::splat(row_height / 1.5)));
+            if components::add_button(ui, add_btn_rect, Id::new("add_graphql_header_button"))
+                .clicked()
+            {
+                self.header.push(("".to_string(), "".to_string()));
+            }
+        });
+    }
+
+    fn send_button(&mut self, ui: &mut Ui, row_height: f32, context: &RequestContext) {
+        let (text, fill) = match &self.state {
+            None => ("Send", color::DODER_BLUE),
+            Some(_) => ("CANCEL", color::CRIMSON),
+        };
+        let button = components::widget_with_size(
+            ui,
+            Vec2::new(80.0, row_height + 4.0),
+            Button::new(WidgetText::from(text).color(color::WHITE)).fill(fill),
+        );
+        if !button.clicked() {
+            return;
+        }
+        if self.state.is_some() {
+            self.state = None;
+            self.snapshot = None;
+            return;
+        }
+        match self.payload(context.variables) {
+            Ok(payload) => {
+                let data = self.request_data(context, &payload, self.method == Method::Get);
+                self.snapshot = Some(data.clone().resolve(context.variables).snapshot());
+                self.state = Some(get_request_promise(data, context.variables.clone()));
+            }
+            Err(err) => self.result = Some(Err(err.into())),
+        }
+    }
+
+    fn introspect(&mut self, context: &RequestContext) {
+        let mut payload = Map::new();
+        payload.insert(
+            "query".to_string(),
+            Value::String(INTROSPECTION_QUERY.to_string()),
+        );
+        // Introspection is always sent by `POST`,the query is too long for some servers.
+        let data = self.request_data(context, &payload, false);
+        self.schema_error = None;
+        self.introspection = Some(get_request_promise(data, context.variables.clone()));
+    }
+
+    fn request_data(
+        &self,
+        context: &RequestContext,
+        payload: &Map<String, Value>,
+        get: bool,
+    ) -> RequestData {
+        RequestData::graphql(context, &self.url, &self.header, &self.auth, payload, get)
+    }
+
+    /// Body of operation,`{{name}}` placeholders are substituted before parsing variables.
+    fn payload(&self, variables: &Variables) -> Result<Map<String, Value>, String> {
+        let mut payload = Map::new();
+        payload.insert(
+            "query".to_string(),
+            Value::String(environment::substitute(&self.query, variables)),
+        );
+        let variables_json = environment::substitute(&self.variables_json, variables);
+        if !variables_json.trim().is_empty() {
+            match serde_json::from_str(&variables_json) {
+                Ok(Value::Object(object)) => {
+                    payload.insert("variables".to_string(), Value::Object(object));
+                }
+                Ok(_) => return Err("Variables should be a json object".to_string()),
+                Err(err) => return Err(format!("Invalid variables: {}", err)),
+            }
+        }
+        if !self.operation_name.trim().is_empty() {
+            payload.insert(
+                "operationName".to_string(),
+                Value::String(self.operation_name.trim().to_string()),
+            );
+        }
+        Ok(payload)
+    }
+
+    /// Check results of sending and introspection.
+    fn poll(&mut self, context: &mut RequestContext) {
+        if let Some(result) = self.state.as_ref().and_then(|state| state.ready()) {
+            if let Some(snapshot) = self.snapshot.take() {
+                context
+                    .history
+                    .push(Entry::new(self.request_name(), snapshot, result));
+            }
+            self.result = Some(result.clone());
+            self.state = None;
+        }
+        if let Some(result) = self.introspection.as_ref().and_then(|state| state.ready()) {
+            let schema = result
+                .clone()
+                .map_err(|err| err.to_string())
+                .and_then(|response| match response.json {
+                    None => Err(format!("Response isn't json,status {}", response.code)),
+                    Some(json) => Schema::from_introspection(&json),
+                });
+            match schema {
+                Ok(schema) => {
+                    self.explored = schema.query_type.clone();
+                    self.schema = Some(schema);
+                }
+                Err(err) => self.schema_error = Some(err),
+            }
+            self.introspection = None;
+        }
+    }
+}
+
+impl Schema {
+    /// Parse response of introspection query.
+    fn from_introspection(json: &Value) -> Result<Self, String> {
+        let schema = match json.get("data").and_then(|data| data.get("__schema")) {
+            Some(schema) => schema,
+            None => {
+                let message = json
+                    .pointer("/errors/0/message")
+                    .and_then(|message| message.as_str())
+                    .unwrap_or("No schema in response");
+                return Err(message.to_string());
+            }
+        };
+        let root = |key: &str| {
+            schema
+                .pointer(&format!("/{}/name", key))
+                .and_then(|name| name.as_str())
+                .map(|name| name.to_string())
+        };
+        let types = schema
+            .get("types")
+            .and_then(|types| types.as_array())
+            .ok_or("No types in schema")?
+            .iter()
+            .map(|typ| {
+                let fields = match typ.get("fields") {
+                    Some(Value::Array(fields)) => fields,
+                    _ => match typ.get("inputFields") {
+                        Some(Value::Array(fields)) => fields,
+                        _ => &[] as &[Value],
+                    },
+                };
+                Type {
+                    kind: text(typ, "kind"),
+                    name: text(typ, "name"),
+                    description: text(typ, "description"),
+                    fields: fields.iter().map(Field::from_introspection).collect(),
+                    enum_values: typ
+                        .get("enumValues")
+                        .and_then(|values| values.as_array())
+                        .map_or(vec![], |values| {
+                            values.iter().map(|value| text(value, "name")).collect()
+                        }),
+                }
+            })
+            .collect();
+        Ok(Self {
+            query_type: root("queryType"),
+            mutation_type: root("mutationType"),
+            subscription_type: root("subscriptionType"),
+            types,
+        })
+    }
+
+    fn find(&self, name: &str) -> Option<&Type> {
+        self.types.iter().find(|typ| typ.name == name)
+    }
+
+    /// Type which a field returns.
+    fn field_type(&self, typ: &str, field: &str) -> Option<&Type> {
+        let field = self
+            .find(typ)?
+            .fields
+            .iter()
+            .find(|item| item.name == field)?;
+        self.find(named_type(&field.type_ref))
+    }
+}
+
+impl Field {
+    fn from_introspection(field: &Value) -> Self {
+        Self {
+            name: text(field, "name"),
+            description: text(field, "description"),
+            type_ref: field.get("type").map(type_ref).unwrap_or_default(),
+            args: field
+                .get("args")
+                .and_then(|args| args.as_array())
+                .map_or(vec![], |args| {
+                    args.iter().map(Field::from_introspection).collect()
+                }),
+        }
+    }
+}
+
+fn text(value: &Value, key: &str) -> String {
+    value
+        .get(key)
+        .and_then(|value| value.as_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Render type reference of introspection,such as `[User!]!`.
+fn type_ref(value: &Value) -> String {
+    let inner = || value.get("ofType").map(type_ref).unwrap_or_default();
+    match value.get("kind").and_then(|kind| kind.as_str()) {
+        Some("NON_NULL") => format!("{}!", inner()),
+        Some("LIST") => format!("[{}]", inner()),
+        _ => text(value, "name"),
+    }
+}
+
+/// `User` of `[User!]!`.
+fn named_type(type_ref: &str) -> &str {
+    type_ref.trim_matches(|c| c == '[' || c == ']' || c == '!')
+}
+
+/// Type of selection set which text ends in,and the field name being typed.
+fn completion<'a, 'b>(schema: &'a Schema, text: &'b str) -> Option<(&'a Type, &'b str)> {
+    // Type of each opened selection set,`None` if it's unknown.
+    let mut stack: Vec<Option<&Type>> = vec![];
+    // Type of selection set which the next `{` opens.
+    let mut pending: Option<&Type> = None;
+    let mut parens = 0;
+    let mut previous: Vec<&str> = vec![];
+    for token in tokenize(text) {
+        match token {
+            "{" => {
+                let shorthand = stack.is_empty() && previous.last().is_none_or(|t| *t == "}");
+                let typ = match pending.take() {
+                    // Shorthand of query such as `{ user { name } }`.
+                    None if shorthand => schema.query_type.as_deref().and_then(|t| schema.find(t)),
+                    typ => typ,
+                };
+                stack.push(typ);
+            }
+            "}" => {
+                stack.pop();
+                pending = None;
+            }
+            "(" => parens += 1,
+            ")" => parens -= 1,
+            _ if parens > 0 => {}
+            name if name.starts_with(|c: char| c.is_alphabetic() || c == '_') => {
+                let before = previous.last().copied();
+                let on_type = before == Some("on")
+                    && (stack.is_empty() || previous.iter().rev().nth(1) == Some(&"..."));
+                pending = if on_type {
+                    schema.find(name)
+                } else if stack.is_empty() {
+                    let root = match name {
+                        "query" => &schema.query_type,
+                        "mutation" => &schema.mutation_type,
+                        "subscription" => &schema.subscription_type,
+                        _ => &None,
+                    };
+                    root.as_deref().and_then(|t| schema.find(t)).or(pending)
+                } else if before == Some("@") || name == "on" {
+                    pending
+                } else {
+                    let parent = stack.last().copied().flatten()?;
+                    schema.field_type(&parent.name, name)
+                };
+            }
+            _ => {}
+        }
+        previous.push(token);
+    }
+    if parens > 0 {
+        return None;
+    }
+    let typ = stack.last().copied().flatten()?;
+    let start = text
+        .rfind(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .map_or(0, |index| index + 1);
+    Some((typ, &text[start..]))
+}
+
+/// Split text into names and punctuators,strings and comments are dropped.
+fn tokenize(text: &str) -> Vec<&str> {
+    let mut tokens = vec![];
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let len = match c {
+            '#' => {
+                rest = rest.find('\n').map_or("", |index| &rest[index..]);
+                continue;
+            }
+            '"' => {
+                let quote = if rest.starts_with("\"\"\"") {
+                    "\"\"\""
+                } else {
+                    "\""
+                };
+                rest = rest[quote.len()..]
+                    .find(quote)
+                    .map_or("", |index| &rest[quote.len() * 2 + index..]);
+                continue;
+            }
+            _ if rest.starts_with("...") => 3,
+            c if c.is_alphanumeric() || c == '_' || c == '$' => rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                .unwrap_or(rest.len()),
+            c if c.is_whitespace() || c == ',' => {
+                rest = &rest[c.len_utf8()..];
+                continue;
+            }
+            c => c.len_utf8(),
+        };
+        tokens.push(&rest[..len]);
+        rest = &rest[len..];
+    }
+    tokens
+}
+
+/// List of types,and fields of the explored type which link to their types.
+fn explorer_view(ui: &mut Ui, schema: &Schema, explored: &mut Option<String>, filter: &mut String) {
+    ui.horizontal(|ui| {
+        for (label, root) in [
+            ("Query", &schema.query_type),
+            ("Mutation", &schema.mutation_type),
+            ("Subscription", &schema.subscription_type),
+        ] {
+            if let Some(root) = root {
+                if ui.button(label).clicked() {
+                    *explored = Some(root.clone());
+                }
+            }
+        }
+        ui.add(
+            TextEdit::singleline(filter)
+                .hint_text("Filter types")
+                .desired_width(150.0),
+        );
+    });
+    ui.add_space(5.0);
+    ui.horizontal_top(|ui| {
+        ui.vertical(|ui| {
+            ui.set_width(180.0);
+            ScrollArea::vertical()
+                .id_source("graphql_types")
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    let filter = filter.to_lowercase();
+                    for typ in &schema.types {
+                        let name = &typ.name;
+                        let visible = if filter.is_empty() {
+                            !name.starts_with("__")
+                        } else {
+                            name.to_lowercase().contains(&filter)
+                        };
+                        if visible
+                            && ui
+                                .selectable_label(explored.as_ref() == Some(name), name)
+                                .clicked()
+                        {
+                            *explored = Some(name.clone());
+                        }
+                    }
+                });
+        });
+        ui.separator();
+        ui.vertical(|ui| {
+            let typ = match explored.as_deref().and_then(|name| schema.find(name)) {
+                None => return,
+                Some(typ) => typ,
+            };
+            ui.label(RichText::new(format!("{} {}", typ.kind, typ.name)).strong());
+            if !typ.description.is_empty() {
+                ui.colored_label(color::GRAY, &typ.description);
+            }
+            ui.add_space(5.0);
+            ScrollArea::vertical()
+                .id_source("graphql_fields")
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    Grid::new("graphql_field_grid")
+                        .num_columns(2)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for field in &typ.fields {
+                                let args = field
+                                    .args
+                                    .iter()
+                                    .map(|arg| format!("{}: {}", arg.name, arg.type_ref))
+                                    .collect::<Vec<String>>();
+                                let signature = match args.is_empty() {
+                                    true => field.name.clone(),
+                                    false => format!("{}({})", field.name, args.join(", ")),
+                                };
+                                let label = ui.label(signature);
+                                if !field.description.is_empty() {
+                                    label.on_hover_text(&field.description);
+                                }
+                                ui.with_layout(Layout::left_to_right(), |ui| {
+                                    if ui.link(&field.type_ref).clicked() {
+                                        *explored = Some(named_type(&field.type_ref).to_string());
+                                    }
+                                });
+                                ui.end_row();
+                            }
+                            for value in &typ.enum_values {
+                                ui.colored_label(color::DARK_ORANGE, value);
+                                ui.end_row();
+                            }
+                        });
+                });
+        });
+    });
+}
+
+#[test]
+fn test_completion() {
+    let json = serde_json::json!({"data": {"__schema": {
+        "queryType": {"name": "Query"},
+        "mutationType": null,
+        "types": [
+            {"kind": "OBJECT", "name": "Query", "fields": [
+                {"name": "user", "args": [{"name": "id", "type": {"kind": "NON_NULL", "ofType": {"kind": "SCALAR", "name": "ID"}}}],
+                 "type": {"kind": "OBJECT", "name": "User"}},
+                {"name": "users", "args": [], "type": {"kind": "NON_NULL", "ofType": {"kind": "LIST", "ofType": {"kind": "NON_NULL", "ofType": {"kind": "INTERFACE", "name": "User"}}}}}
+            ]},
+            {"kind": "INTERFACE", "name": "User", "fields": [
+                {"name": "name", "args": [], "type": {"kind": "SCALAR", "name": "String"}},
+                {"name": "friends", "args": [], "type": {"kind": "LIST", "ofType": {"kind": "OBJECT", "name": "User"}}}
+            ]},
+            {"kind": "OBJECT", "name": "Admin", "fields": [
+                {"name": "role", "args": [], "type": {"kind": "SCALAR", "name": "String"}}
+            ]}
+        ]
+    }}});
+    let schema = Schema::from_introspection(&json).unwrap();
+    assert_eq!(schema.find("Query").unwrap().fields[1].type_ref, "[User!]!");
+    assert_eq!(
+        schema.find("Query").unwrap().fields[0].args[0].type_ref,
+        "ID!"
+    );
+
+    let check = |text: &str, expected: Option<(&str, &str)>| {
+        let result = completion(&schema, text).map(|(typ, prefix)| (typ.name.as_str(), prefix));
+        assert_eq!(result, expected, "{}", text);
+    };
+    check("{ us", Some(("Query", "us")));
+    check(
+        "query Q($id: ID!) { user(id: $id) { na",
+        Some(("User", "na")),
+    );
+    check("{ u: user(id: \"{\") { friends { ", Some(("User", "")));
+    check("{ users { ... on Admin { ro", Some(("Admin", "ro")));
+    check("{ user { name } ", Some(("Query", "")));
+    check("{ user(id: ", None);
+    check("fragment F on Admin { # {\n r", Some(("Admin", "r")));
+    check("mutation { a", None);
+
+    let mut graphql = GraphQL {
+        query: "query Q($id: ID!) { user(id: $id) { name } }".to_string(),
+        variables_json: "{\"id\": \"{{id}}\"}".to_string(),
+        operation_name: "Q".to_string(),
+        ..Default::default()
+    };
+    let variables = vec![("id".to_string(), "7".to_string())];
+    let payload = graphql.payload(&variables.into_iter().collect()).unwrap();
+    assert_eq!(payload["variables"]["id"], "7");
+    assert_eq!(payload["operationName"], "Q");
+    graphql.variables_json = "[1]".to_string();
+    assert!(graphql.payload(&Default::default()).is_err());
+}
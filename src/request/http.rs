@@ -1,4 +1,21 @@
-use crate::request::Request;
+use crate::collection::{self, Defaults};
+use crate::curl;
+use crate::environment::{self, Variables};
+use crate::history::{Entry, Snapshot};
+use crate::request::assertion::{self, Assertion, Outcome};
+use crate::request::auth::{self, ApiKeyLocation, Auth};
+use crate::request::body::{self, BodyKind, Image};
+use crate::request::client::{self, ClientOptions};
+use crate::request::extraction::{self, Extracted, Extraction};
+use crate::request::json;
+use crate::request::oauth2::TokenClient;
+use crate::request::proxy::Proxy;
+use crate::request::redirect::{Follow, Hop, Redirect};
+use crate::request::response::{BodyView, Response, ResponseTab};
+use crate::request::sse::EventStream;
+use crate::request::tls::{Tls, TlsInfo};
+use crate::request::{Request, RequestContext};
+use crate::setting::{Settings, Timeouts};
 use crate::{color, components, style, Visuals};
 
 use crate::egui::{FontSelection, Vec2};
@@ -6,19 +23,80 @@ use crate::style::get_row_height;
 use eframe::egui;
 use eframe::egui::text::LayoutJob;
 use eframe::egui::{
-    Align, Button, CollapsingHeader, ComboBox, FontId, Id, Layout, Pos2, Rect, Rounding,
+    Align, Button, CollapsingHeader, ComboBox, FontId, Grid, Id, Layout, Pos2, Rect, Rounding,
     ScrollArea, Stroke, TextEdit, TextStyle, Ui, WidgetText,
 };
+use futures_util::future::{self, Either};
 use poll_promise::Promise;
 use rand::{distributions::Alphanumeric, Rng};
-use reqwest::blocking::multipart;
+use reqwest::header::{HeaderValue, AUTHORIZATION, LOCATION, WWW_AUTHENTICATE};
+use reqwest::multipart;
 use reqwest::{StatusCode, Url};
 use serde::{Deserialize, Serialize};
 use std::ffi::OsStr;
+use std::fmt::{Display, Formatter};
 use std::ops::Add;
 use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+use std::time::Instant;
+use tokio::runtime::Runtime;
+use tokio::sync::Notify;
 
-type RequestResult = Result<Response, String>;
+pub type RequestResult = Result<Response, RequestError>;
+
+/// Failure of sending request,timeouts are told apart from others.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RequestError {
+    Timeout(String),
+    Other(String),
+}
+
+impl Display for RequestError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RequestError::Timeout(err) => write!(f, "Timeout: {}", err),
+            RequestError::Other(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl From<String> for RequestError {
+    fn from(err: String) -> Self {
+        RequestError::Other(err)
+    }
+}
+
+impl From<reqwest::Error> for RequestError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            RequestError::Timeout(err.to_string())
+        } else {
+            RequestError::Other(err.to_string())
+        }
+    }
+}
+
+/// Request which is being sent,the transfer is aborted when it's dropped.
+pub struct Sending {
+    promise: Promise<RequestResult>,
+    cancel: Arc<Notify>,
+}
+
+impl Sending {
+    pub fn ready(&self) -> Option<&RequestResult> {
+        self.promise.ready()
+    }
+
+    pub fn block_until_ready(&self) -> &RequestResult {
+        self.promise.block_until_ready()
+    }
+}
+
+impl Drop for Sending {
+    fn drop(&mut self) {
+        self.cancel.notify_one();
+    }
+}
 
 #[derive(Deserialize, Serialize)]
 pub struct Http {
@@ -31,49 +109,79 @@ pub struct Http {
     text_param: String,
     form_param: Vec<(String, String, Option<PathBuf>, FormParamType)>,
     param_type: ParamType,
-    show_header: bool,
+    #[serde(default)]
+    auth: Auth,
+    #[serde(default)]
+    request_tab: RequestTab,
+    /// Override timeouts of settings.
+    #[serde(default)]
+    timeouts: Option<Timeouts>,
+    /// Override proxy of settings.
+    #[serde(default)]
+    proxy: Option<Proxy>,
+    /// Added to TLS options of settings.
+    #[serde(default)]
+    tls: Tls,
+    #[serde(default)]
+    redirect: Redirect,
+    /// Checked when response is received.
+    #[serde(default)]
+    assertions: Vec<Assertion>,
+    #[serde(skip)]
+    outcomes: Vec<Outcome>,
+    /// Run when response is received,values are stored into active environment.
+    #[serde(default)]
+    extractions: Vec<Extraction>,
+    #[serde(skip)]
+    extracted: Vec<Extracted>,
+    /// Send without the cookie jar of session.
+    #[serde(default)]
+    no_cookies: bool,
+    #[serde(skip)]
+    response_tab: ResponseTab,
+    #[serde(skip)]
+    body_view: BodyView,
     // TODO Discuss this structs' impl
     #[serde(skip)]
     result: Option<RequestResult>,
     // TODO add error handle
     #[serde(skip)]
-    state: Option<Promise<RequestResult>>,
+    state: Option<Sending>,
+    /// Request which is sending,recorded in history when it's finished.
+    #[serde(skip)]
+    snapshot: Option<Snapshot>,
+    #[serde(skip)]
+    beautify_error: Option<String>,
 }
 
-#[derive(Clone)]
-struct Response {
-    body: String,
-    size: Option<u64>,
-    code: StatusCode,
+#[derive(Deserialize, Serialize, Eq, PartialEq, Clone, Copy, Default)]
+enum RequestTab {
+    #[default]
+    Header,
+    Param,
+    Auth,
+    Tests,
+    Extract,
+    Settings,
 }
 
-#[derive(Deserialize, Serialize, Eq, PartialEq, Debug, Clone)]
+#[derive(Deserialize, Serialize, Eq, PartialEq, Debug, Clone, Default)]
 pub enum FormParamType {
     File,
+    #[default]
     Text,
 }
 
-impl Default for FormParamType {
-    fn default() -> Self {
-        Self::Text
-    }
-}
-
-#[derive(Deserialize, Serialize, Eq, PartialEq, Clone, Copy)]
+#[derive(Deserialize, Serialize, Eq, PartialEq, Debug, Clone, Copy, Default)]
 enum ParamType {
     None,
     FormData,
+    #[default]
     Json,
     Query,
     Other,
 }
 
-impl Default for ParamType {
-    fn default() -> Self {
-        Self::Json
-    }
-}
-
 impl ParamType {
     fn get_content_type(&self) -> String {
         match self {
@@ -95,9 +203,23 @@ impl Default for Http {
             text_param: "".to_string(),
             form_param: vec![],
             param_type: Default::default(),
-            show_header: true,
+            auth: Default::default(),
+            request_tab: Default::default(),
+            timeouts: None,
+            proxy: None,
+            tls: Default::default(),
+            redirect: Default::default(),
+            assertions: vec![],
+            outcomes: vec![],
+            extractions: vec![],
+            extracted: vec![],
+            no_cookies: false,
+            response_tab: Default::default(),
+            body_view: Default::default(),
             result: Option::default(),
             state: Option::default(),
+            snapshot: None,
+            beautify_error: None,
         }
     }
 }
@@ -107,7 +229,7 @@ impl Default for Http {
 //     Ok(Response),
 // }
 
-fn get_uuid() -> String {
+pub fn get_uuid() -> String {
     rand::thread_rng()
         .sample_iter(&Alphanumeric)
         .take(7)
@@ -123,7 +245,9 @@ impl Request for Http {
         self.name.as_str()
     }
 
-    fn view(&mut self, ui: &mut Ui) {
+    fn view(&mut self, ui: &mut Ui, context: &mut RequestContext) {
+        let variables = context.variables;
+        let defaults = context.defaults;
         let (id, row_height) = crate::style::get_row_height(ui);
         ui.add_space(10.0);
         ui.with_layout(Layout::left_to_right().with_cross_align(Align::Min), |ui| {
@@ -157,7 +281,7 @@ impl Request for Http {
             ui.style_mut().visuals.widgets = crate::style::get_widgets(5.0);
             ui.add_space(19.0);
             let mut job = LayoutJob::simple(
-                format!("{:?}", &self.method),
+                self.method.label().to_string(),
                 id.clone(),
                 color::BLACK,
                 50.0,
@@ -168,11 +292,25 @@ impl Request for Http {
                 .show_ui(ui, |ui| {
                     self.method_select(ui);
                 });
+            if let Method::Custom(method) = &mut self.method {
+                ui.add_space(5.0);
+                TextEdit::singleline(method)
+                    .font(FontSelection::Style(TextStyle::Button))
+                    .hint_text("PROPFIND")
+                    .desired_width(100.0)
+                    .show(ui);
+            }
             ui.add_space(10.0);
             ui.style_mut().visuals.widgets = crate::style::get_widgets(5.0);
+            let mut layouter = |ui: &Ui, string: &str, wrap_width: f32| {
+                let font_id = FontSelection::Style(TextStyle::Button).resolve(ui.style());
+                ui.fonts().layout_job(environment::highlight(
+                    string, variables, font_id, wrap_width,
+                ))
+            };
             TextEdit::singleline(&mut self.url)
-                .font(FontSelection::Style(TextStyle::Button))
                 .desired_width(ui.available_width() - 110.0)
+                .layouter(&mut layouter)
                 .show(ui);
 
             // Button::new("SEND");
@@ -180,11 +318,44 @@ impl Request for Http {
             ui.add_space(5.0);
 
             // ui.with_layout(Layout::left_to_right().with_cross_align(Align::Max), |ui| {
-            self.send_button(ui, id.clone(), row_height)
+            self.send_button(ui, id.clone(), row_height, context)
             // });
         });
-        if let Some(Result::Err(error_text)) = &self.result {
+        if !defaults.base_url.is_empty() || !defaults.header.is_empty() {
+            ui.add_space(5.0);
+            ui.horizontal(|ui| {
+                ui.add_space(19.0);
+                ui.colored_label(
+                    color::GRAY,
+                    format!(
+                        "Inherited from folder: base URL {} ,{} headers",
+                        match defaults.base_url.as_str() {
+                            "" => "-",
+                            base_url => base_url,
+                        },
+                        defaults.header.len()
+                    ),
+                );
+            });
+        }
+        let unresolved = self.unresolved_variables(variables);
+        if !unresolved.is_empty() {
+            ui.add_space(5.0);
+            ui.horizontal(|ui| {
+                ui.add_space(19.0);
+                ui.colored_label(
+                    color::CRIMSON,
+                    format!("Unresolved variables: {}", unresolved.join(", ")),
+                );
+            });
+        }
+        if let Some(Result::Err(error)) = &self.result {
             ui.add_space(15.0);
+            let text_color = match error {
+                RequestError::Timeout(_) => color::DARK_ORANGE,
+                RequestError::Other(_) => color::CRIMSON,
+            };
+            let error_text = error.to_string();
             let clear_btn_res = ui
                 .vertical_centered(|ui| {
                     let mut error_text = error_text.as_str();
@@ -202,7 +373,7 @@ impl Request for Http {
                             components::close_button(ui, clear_btn_rect, Id::new("clear_btn_rect"));
 
                         eframe::egui::TextEdit::multiline(&mut error_text) // for cursor height
-                            .text_color(color::CRIMSON)
+                            .text_color(text_color)
                             .desired_width(ui.available_width() - 25.0)
                             .desired_rows(1)
                             .show(ui);
@@ -226,12 +397,24 @@ impl Request for Http {
                 ui.add_space(5.0);
                 ui.style_mut().visuals.widgets = crate::style::get_widgets(1.0);
                 ui.horizontal(|ui| {
-                    ui.selectable_value(&mut self.show_header, true, "HEADER");
-                    ui.selectable_value(&mut self.show_header, false, "PARAM");
+                    ui.selectable_value(&mut self.request_tab, RequestTab::Header, "HEADER");
+                    ui.selectable_value(&mut self.request_tab, RequestTab::Param, "PARAM");
+                    ui.selectable_value(&mut self.request_tab, RequestTab::Auth, "AUTH");
+                    ui.selectable_value(
+                        &mut self.request_tab,
+                        RequestTab::Tests,
+                        format!("TESTS ({})", self.assertions.len()),
+                    );
+                    ui.selectable_value(
+                        &mut self.request_tab,
+                        RequestTab::Extract,
+                        format!("EXTRACT ({})", self.extractions.len()),
+                    );
+                    ui.selectable_value(&mut self.request_tab, RequestTab::Settings, "SETTINGS");
                 });
                 ui.add_space(5.0);
-                match self.show_header {
-                    true => {
+                match self.request_tab {
+                    RequestTab::Header => {
                         let group_rect = ui
                             .group(|ui| {
                                 ui.set_width(ui.available_width());
@@ -248,13 +431,11 @@ impl Request for Http {
                                                 ui.add_space(2.0);
                                                 label += 1;
                                                 !ui.horizontal(|ui| {
-                                                    ui.add(
-                                                        TextEdit::singleline(key)
-                                                            .desired_width(col_width),
+                                                    environment::variable_text_edit(
+                                                        ui, key, variables, col_width,
                                                     );
-                                                    ui.add(
-                                                        TextEdit::singleline(value)
-                                                            .desired_width(col_width),
+                                                    environment::variable_text_edit(
+                                                        ui, value, variables, col_width,
                                                     );
 
                                                     let clear_btn_rect =
@@ -306,8 +487,68 @@ impl Request for Http {
                             Stroke::new(2.0, color::GRAY),
                         );
                     }
-                    false => {
-                        self.param_view(ui);
+                    RequestTab::Param => {
+                        self.param_view(ui, variables);
+                    }
+                    RequestTab::Auth => {
+                        let group_rect = ui
+                            .group(|ui| {
+                                ui.set_width(ui.available_width());
+                                ui.style_mut().visuals.widgets = crate::style::get_widgets(1.0);
+                                self.auth.view(ui, variables);
+                            })
+                            .response
+                            .rect;
+                        ui.painter_at(group_rect).rect_stroke(
+                            group_rect,
+                            Rounding::none(),
+                            Stroke::new(2.0, color::GRAY),
+                        );
+                    }
+                    RequestTab::Tests => {
+                        let group_rect = ui
+                            .group(|ui| {
+                                ui.set_width(ui.available_width());
+                                ui.style_mut().visuals.widgets = crate::style::get_widgets(1.0);
+                                assertion::view(ui, &mut self.assertions);
+                            })
+                            .response
+                            .rect;
+                        ui.painter_at(group_rect).rect_stroke(
+                            group_rect,
+                            Rounding::none(),
+                            Stroke::new(2.0, color::GRAY),
+                        );
+                    }
+                    RequestTab::Extract => {
+                        let group_rect = ui
+                            .group(|ui| {
+                                ui.set_width(ui.available_width());
+                                ui.style_mut().visuals.widgets = crate::style::get_widgets(1.0);
+                                extraction::view(ui, &mut self.extractions);
+                            })
+                            .response
+                            .rect;
+                        ui.painter_at(group_rect).rect_stroke(
+                            group_rect,
+                            Rounding::none(),
+                            Stroke::new(2.0, color::GRAY),
+                        );
+                    }
+                    RequestTab::Settings => {
+                        let group_rect = ui
+                            .group(|ui| {
+                                ui.set_width(ui.available_width());
+                                ui.style_mut().visuals.widgets = crate::style::get_widgets(1.0);
+                                self.settings_view(ui, context.settings);
+                            })
+                            .response
+                            .rect;
+                        ui.painter_at(group_rect).rect_stroke(
+                            group_rect,
+                            Rounding::none(),
+                            Stroke::new(2.0, color::GRAY),
+                        );
                     }
                 }
             });
@@ -317,33 +558,19 @@ impl Request for Http {
             .default_open(true)
             .show(ui, |ui| {
                 if let Some(Result::Ok(response)) = &self.result {
-                    ui.label(format!(
-                        "{} {} {}",
-                        response.code.as_str(),
-                        response.code.canonical_reason().unwrap_or(""),
-                        match response.size {
-                            None => {
-                                "".to_string()
-                            }
-                            Some(size) => {
-                                format!(" ,Size: {}", size)
-                            }
-                        }
-                    ));
-
-                    ScrollArea::vertical()
-                        .max_height(ui.available_height())
-                        .show(ui, |ui| {
-                            ui.group(|ui| {
-                                ui.set_width(ui.available_width());
-                                ui.vertical_centered_justified(|ui| {
-                                    ui.add_enabled_ui(true, |ui| {
-                                        let mut response_body = response.body.as_str();
-                                        ui.text_edit_multiline(&mut response_body)
-                                    });
-                                })
-                            })
-                        });
+                    if !self.outcomes.is_empty() {
+                        assertion::outcomes_view(ui, &self.outcomes);
+                        ui.add_space(5.0);
+                    }
+                    if !self.extracted.is_empty() {
+                        extraction::extracted_view(
+                            ui,
+                            &self.extracted,
+                            !context.session.is_empty(),
+                        );
+                        ui.add_space(5.0);
+                    }
+                    response.view(ui, &mut self.response_tab, &mut self.body_view);
                 }
             });
     }
@@ -351,9 +578,36 @@ impl Request for Http {
     fn get_id(&self) -> String {
         self.id.to_string()
     }
+
+    fn context_menu(&self, ui: &mut Ui, defaults: &Defaults, variables: &Variables) {
+        if ui.button("Copy as cURL").clicked() {
+            ui.output().copied_text = self.to_curl(defaults, variables);
+            ui.close_menu();
+        }
+    }
 }
 
 impl Http {
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    pub fn set_tls(&mut self, tls: Tls) {
+        self.tls = tls;
+    }
+
+    pub fn set_redirect(&mut self, redirect: Redirect) {
+        self.redirect = redirect;
+    }
+
+    pub fn assertions(&self) -> &[Assertion] {
+        &self.assertions
+    }
+
+    pub fn extractions(&self) -> &[Extraction] {
+        &self.extractions
+    }
+
     pub fn from_curl(
         url: String,
         method: String,
@@ -361,23 +615,34 @@ impl Http {
         text_param: String,
         form_param: Vec<(String, String, Option<PathBuf>, FormParamType)>,
         param_type: String,
+        auth: Auth,
     ) -> Result<Self, String> {
-        // TODO refactor
-        let method = match method.as_str() {
-            "GET" => Method::Get,
-            "DELETE" => Method::Delete,
-            "PUT" => Method::Put,
-            "POST" => Method::Post,
-            "PATCH" => Method::Patch,
-            _ => {
-                return Err("No such method".to_string());
+        let method = match Method::from_name(&method) {
+            Some(method) => method,
+            None => {
+                return Err(format!("No such method: {}", method));
             }
         };
 
+        let mut url = url;
+        let mut form_param = form_param;
         let param_type = if param_type.contains("application/json") {
             ParamType::Json
         } else if param_type.contains("multipart/form-data") {
             ParamType::FormData
+        } else if method.is_query_only() {
+            // Move query of url into query params,which is the only param type of `GET`.
+            if let Ok(mut parsed) = Url::parse(&url) {
+                if parsed.query().is_some() {
+                    form_param = parsed
+                        .query_pairs()
+                        .map(|(k, v)| (k.to_string(), v.to_string(), None, FormParamType::Text))
+                        .collect();
+                    parsed.set_query(None);
+                    url = parsed.to_string();
+                }
+            }
+            ParamType::Query
         } else {
             ParamType::Other
         };
@@ -391,13 +656,263 @@ impl Http {
             text_param,
             form_param,
             param_type,
+            auth,
             ..Default::default()
         })
     }
 
+    /// Export as a cURL command with settings inherited from folders,which can be imported by `parse_curl`.
+    /// `variables` are used to find cached OAuth2 token,placeholders are kept.
+    pub fn to_curl(&self, defaults: &Defaults, variables: &Variables) -> String {
+        let mut url = collection::join_url(&defaults.base_url, &self.url);
+        let mut query: Vec<(&String, &String)> = vec![];
+        if self.param_type == ParamType::Query {
+            query.extend(self.form_param.iter().map(|(k, v, ..)| (k, v)));
+        }
+        if let Auth::ApiKey {
+            key,
+            value,
+            location: ApiKeyLocation::Query,
+        } = &self.auth
+        {
+            query.push((key, value));
+        }
+        if !query.is_empty() {
+            match Url::parse(&url) {
+                Ok(mut parsed) => {
+                    parsed.query_pairs_mut().extend_pairs(query);
+                    url = parsed.to_string();
+                }
+                Err(_) => {
+                    let query = query
+                        .iter()
+                        .map(|(k, v)| format!("{}={}", k, v))
+                        .collect::<Vec<String>>()
+                        .join("&");
+                    url = format!("{}?{}", url, query);
+                }
+            }
+        }
+
+        let mut args = vec![format!("curl {}", curl::quote(&url))];
+        if self.redirect.follow {
+            args.push(format!("-L --max-redirs {}", self.redirect.max));
+        }
+        if self.tls.insecure {
+            args.push("--insecure".to_string());
+        }
+        for ca_file in self
+            .tls
+            .ca_files
+            .iter()
+            .filter(|path| !path.as_os_str().is_empty())
+        {
+            args.push(format!(
+                "--cacert {}",
+                curl::quote(&ca_file.to_string_lossy())
+            ));
+        }
+        let host = Url::parse(&url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_default();
+        if let Some(identity) = self.tls.identity(&host) {
+            let mut cert = identity.cert.to_string_lossy().to_string();
+            if !identity.password.is_empty() {
+                cert = format!("{}:{}", cert, identity.password);
+            }
+            args.push(format!("--cert {}", curl::quote(&cert)));
+            if let Some(key) = &identity.key {
+                args.push(format!("--key {}", curl::quote(&key.to_string_lossy())));
+            }
+        }
+        // `-X HEAD` make curl wait for a body which never comes.
+        if self.method == Method::Head {
+            args.push("--head".to_string());
+        } else {
+            args.push(format!("-X {}", curl::quote(self.method.as_str())));
+        }
+        for (k, v) in &defaults.merge_header(&self.header) {
+            args.push(format!("-H {}", curl::quote(&format!("{}: {}", k, v))));
+        }
+        match &self.auth {
+            Auth::None => {}
+            Auth::Basic { username, password } => {
+                args.push(format!(
+                    "-u {}",
+                    curl::quote(&format!("{}:{}", username, password))
+                ));
+            }
+            Auth::Digest { username, password } => {
+                args.push(format!(
+                    "--digest -u {}",
+                    curl::quote(&format!("{}:{}", username, password))
+                ));
+            }
+            Auth::Bearer { token } => {
+                args.push(format!(
+                    "-H {}",
+                    curl::quote(&format!("Authorization: Bearer {}", token))
+                ));
+            }
+            // Only a token which has been fetched can be exported.
+            Auth::OAuth2(oauth2) => {
+                if let Some(token) = oauth2.resolve(variables).cached_token() {
+                    args.push(format!(
+                        "-H {}",
+                        curl::quote(&format!("Authorization: Bearer {}", token.access_token))
+                    ));
+                }
+            }
+            Auth::ApiKey {
+                key,
+                value,
+                location: ApiKeyLocation::Header,
+            } => {
+                args.push(format!(
+                    "-H {}",
+                    curl::quote(&format!("{}: {}", key, value))
+                ));
+            }
+            Auth::ApiKey { .. } => {}
+        }
+        let has_content_type = self
+            .header
+            .iter()
+            .any(|(k, _)| k.eq_ignore_ascii_case("content-type"));
+        match self.param_type {
+            ParamType::FormData => {
+                for (k, v, path, typ) in &self.form_param {
+                    match typ {
+                        FormParamType::File => {
+                            let path = match path {
+                                None => "".to_string(),
+                                Some(path) => path.to_string_lossy().to_string(),
+                            };
+                            args.push(format!("-F {}", curl::quote(&format!("{}=@{}", k, path))));
+                        }
+                        FormParamType::Text => {
+                            // `-F` treat `@`,`<` and `;` as special characters.
+                            let flag =
+                                if v.starts_with('@') || v.starts_with('<') || v.contains(';') {
+                                    "--form-string"
+                                } else {
+                                    "-F"
+                                };
+                            args.push(format!("{} {}", flag, curl::quote(&format!("{}={}", k, v))));
+                        }
+                    }
+                }
+            }
+            ParamType::Json | ParamType::Other => {
+                if self.param_type == ParamType::Json && !has_content_type {
+                    args.push(format!(
+                        "-H {}",
+                        curl::quote(&format!(
+                            "Content-Type: {}",
+                            self.param_type.get_content_type()
+                        ))
+                    ));
+                }
+                if !self.text_param.is_empty() {
+                    args.push(format!("--data-raw {}", curl::quote(&self.text_param)));
+                }
+            }
+            ParamType::Query | ParamType::None => {}
+        }
+        args.join(" \\\n  ")
+    }
+
+    pub fn url(&self) -> &str {
+        self.url.as_str()
+    }
+
+    pub fn method_name(&self) -> String {
+        self.method.as_str().to_string()
+    }
+
+    fn request_data(&self, context: &RequestContext) -> RequestData {
+        let defaults = context.defaults;
+        RequestData {
+            timeouts: self.timeouts.unwrap_or(context.settings.timeouts),
+            proxy: self
+                .proxy
+                .clone()
+                .unwrap_or_else(|| context.settings.proxy.clone()),
+            tls: self.tls.merge(&context.settings.tls),
+            redirect: self.redirect,
+            session: context.session.to_string(),
+            cookies: !self.no_cookies,
+            method: self.method.clone(),
+            param_type: self.param_type,
+            base_url: defaults.base_url.clone(),
+            url: self.url.clone(),
+            header: defaults.merge_header(&self.header),
+            text_param: self.text_param.clone(),
+            form_param: self.form_param.clone(),
+            auth: self.auth.clone(),
+        }
+    }
+
+    /// Send this request in another thread.
+    pub fn send(&self, context: &RequestContext) -> Sending {
+        get_request_promise(self.request_data(context), context.variables.clone())
+    }
+
+    fn settings_view(&mut self, ui: &mut Ui, settings: &Settings) {
+        let mut overridden = self.timeouts.is_some();
+        if ui
+            .checkbox(&mut overridden, "Override timeouts of settings")
+            .changed()
+        {
+            self.timeouts = overridden.then_some(settings.timeouts);
+        }
+        match &mut self.timeouts {
+            None => {
+                ui.colored_label(
+                    color::GRAY,
+                    format!(
+                        "Connect timeout {} s ,total timeout {} s ,0 means no limit",
+                        settings.timeouts.connect, settings.timeouts.total
+                    ),
+                );
+            }
+            Some(timeouts) => {
+                Grid::new("request_timeouts_grid")
+                    .num_columns(2)
+                    .show(ui, |ui| timeouts.grid_view(ui));
+            }
+        }
+        ui.add_space(5.0);
+        let mut overridden = self.proxy.is_some();
+        if ui
+            .checkbox(&mut overridden, "Override proxy of settings")
+            .changed()
+        {
+            self.proxy = overridden.then(|| settings.proxy.clone());
+        }
+        if let Some(proxy) = &mut self.proxy {
+            Grid::new("request_proxy_grid")
+                .num_columns(2)
+                .show(ui, |ui| proxy.grid_view(ui));
+        }
+        ui.add_space(5.0);
+        self.redirect.view(ui);
+        ui.add_space(5.0);
+        CollapsingHeader::new("TLS")
+            .default_open(!self.tls.is_empty())
+            .show(ui, |ui| {
+                ui.colored_label(color::GRAY, "Added to TLS options of settings");
+                self.tls.view(ui, "request_tls");
+            });
+        ui.add_space(5.0);
+        ui.checkbox(&mut self.no_cookies, "Don't send cookies")
+            .on_hover_text("Cookies of response aren't saved either");
+    }
+
     fn param_type_view(&mut self, ui: &mut Ui) {
-        ui.horizontal(|ui| match self.method {
-            Method::Get => {
+        ui.horizontal(|ui| match self.method.is_query_only() {
+            true => {
                 ui.vertical_centered(|ui| {
                     ui.with_layout(Layout::left_to_right(), |ui| {
                         self.param_type = ParamType::Query;
@@ -405,7 +920,7 @@ impl Http {
                     })
                 });
             }
-            _ => {
+            false => {
                 ui.vertical_centered(|ui| {
                     ui.with_layout(Layout::left_to_right(), |ui| {
                         ui.selectable_value(&mut self.param_type, ParamType::Json, "json");
@@ -418,7 +933,7 @@ impl Http {
         });
     }
 
-    fn param_view(&mut self, ui: &mut Ui) {
+    fn param_view(&mut self, ui: &mut Ui, variables: &Variables) {
         // TODO open an issue to trace this style problem:Group's bottom line is lighter than others when form data instead of raw/json.
         let group_rect = ui
             .group(|ui| {
@@ -434,7 +949,7 @@ impl Http {
                             ui.style_mut().visuals.widgets = crate::style::get_widgets(1.0);
                             match self.param_type {
                                 ParamType::FormData => {
-                                    self.form_data_param_view(ui);
+                                    self.form_data_param_view(ui, variables);
                                 }
                                 ParamType::Json => {
                                     self.raw_param_view(ui);
@@ -443,7 +958,7 @@ impl Http {
                                     self.raw_param_view(ui);
                                 }
                                 ParamType::Query => {
-                                    self.query_param_view(ui);
+                                    self.query_param_view(ui, variables);
                                 }
                                 _ => {
                                     ui.set_width(ui.available_width());
@@ -463,7 +978,7 @@ impl Http {
         );
     }
 
-    fn form_data_param_view(&mut self, ui: &mut Ui) {
+    fn form_data_param_view(&mut self, ui: &mut Ui, variables: &Variables) {
         ui.set_width(ui.available_width());
         let (font_id, row_height) = crate::style::get_row_height(ui);
         let mut label = 0;
@@ -472,7 +987,7 @@ impl Http {
             .retain_mut(|(key, value, path_buf, form_param_type)| {
                 ui.add_space(2.0);
                 !ui.with_layout(Layout::left_to_right().with_cross_align(Align::Min), |ui| {
-                    ui.add(TextEdit::singleline(key).desired_width(col_width));
+                    environment::variable_text_edit(ui, key, variables, col_width);
 
                     label += 1;
                     // TODO center
@@ -515,11 +1030,8 @@ impl Http {
                             }
                         }
                         FormParamType::Text => {
-                            components::widget_with_size(
-                                ui,
-                                Vec2::new(col_width, row_height),
-                                TextEdit::singleline(value),
-                            );
+                            ui.add_space(5.0);
+                            environment::variable_text_edit(ui, value, variables, col_width);
                         }
                     }
 
@@ -574,14 +1086,40 @@ impl Http {
     }
 
     fn raw_param_view(&mut self, ui: &mut Ui) {
+        if self.param_type == ParamType::Json {
+            ui.horizontal(|ui| {
+                if ui.button("Beautify").clicked() {
+                    self.beautify_error = match json::beautify(&self.text_param) {
+                        Ok(text) => {
+                            self.text_param = text;
+                            None
+                        }
+                        Err(err) => Some(err),
+                    };
+                }
+                if let Some(err) = &self.beautify_error {
+                    ui.colored_label(color::CRIMSON, err);
+                }
+            });
+            ui.add_space(5.0);
+        }
         ui.horizontal(|ui| {
             ui.vertical_centered_justified(|ui| {
-                ui.text_edit_multiline(&mut self.text_param);
+                if self.param_type == ParamType::Json {
+                    let mut layouter = |ui: &Ui, string: &str, wrap_width: f32| {
+                        let font_id = FontSelection::default().resolve(ui.style());
+                        ui.fonts()
+                            .layout_job(json::highlight(string, font_id, wrap_width))
+                    };
+                    ui.add(TextEdit::multiline(&mut self.text_param).layouter(&mut layouter));
+                } else {
+                    ui.text_edit_multiline(&mut self.text_param);
+                }
             })
         });
     }
 
-    fn query_param_view(&mut self, ui: &mut Ui) {
+    fn query_param_view(&mut self, ui: &mut Ui, variables: &Variables) {
         let col_width = (ui.available_width() - 70.0) / 2.0;
 
         let (_, row_height) = style::get_row_height(ui);
@@ -591,8 +1129,8 @@ impl Http {
             label += 1;
             ui.add_space(2.0);
             !ui.horizontal(|ui| {
-                ui.add(TextEdit::singleline(key).desired_width(col_width));
-                ui.add(TextEdit::singleline(value).desired_width(col_width));
+                environment::variable_text_edit(ui, key, variables, col_width);
+                environment::variable_text_edit(ui, value, variables, col_width);
                 let clear_btn_rect = Self::get_next_del_btn(row_height, ui);
                 ui.add_space(row_height);
                 components::close_button(
@@ -639,44 +1177,64 @@ impl Http {
         //     bottom: 5.0,
         // };
         // ui.style_mut().visuals.widgets = crate::style::get_widgets();
-        // TODO Better impl
-        if ui
-            .selectable_value(&mut self.method, Method::Get, "Get")
-            .changed()
-        {
-            self.form_param = vec![];
-        };
-        if ui
-            .selectable_value(&mut self.method, Method::Post, "Post")
-            .changed()
-        {
-            self.form_param = vec![];
-            self.param_type = ParamType::FormData;
-        };
-        if ui
-            .selectable_value(&mut self.method, Method::Put, "Put")
-            .changed()
-        {
-            self.form_param = vec![];
-            self.param_type = ParamType::FormData;
-        };
-        if ui
-            .selectable_value(&mut self.method, Method::Delete, "Delete")
-            .changed()
-        {
-            self.form_param = vec![];
-            self.param_type = ParamType::FormData;
-        };
-        if ui
-            .selectable_value(&mut self.method, Method::Patch, "Patch")
-            .changed()
-        {
+        let mut changed = false;
+        for method in Method::PRESETS {
+            let label = method.label().to_string();
+            changed |= ui
+                .selectable_value(&mut self.method, method, label)
+                .changed();
+        }
+        let is_custom = matches!(self.method, Method::Custom(_));
+        if ui.selectable_label(is_custom, "Custom").clicked() && !is_custom {
+            self.method = Method::Custom("".to_string());
+            changed = true;
+        }
+        if changed {
             self.form_param = vec![];
-            self.param_type = ParamType::FormData;
-        };
+            if !self.method.is_query_only() {
+                self.param_type = ParamType::FormData;
+            }
+        }
     }
 
-    fn send_button(&mut self, ui: &mut Ui, id: FontId, row_height: f32) {
+    /// Names of placeholders in url,headers,params and body which can't be resolved.
+    fn unresolved_variables(&self, variables: &Variables) -> Vec<String> {
+        let mut result = environment::unresolved(&self.url, variables);
+        for (key, value) in &self.header {
+            result.extend(environment::unresolved(key, variables));
+            result.extend(environment::unresolved(value, variables));
+        }
+        match self.param_type {
+            ParamType::FormData | ParamType::Query => {
+                for (key, value, ..) in &self.form_param {
+                    result.extend(environment::unresolved(key, variables));
+                    result.extend(environment::unresolved(value, variables));
+                }
+            }
+            ParamType::Json | ParamType::Other => {
+                result.extend(environment::unresolved(&self.text_param, variables));
+            }
+            ParamType::None => {}
+        }
+        for text in self.auth.texts() {
+            result.extend(environment::unresolved(text, variables));
+        }
+        let mut names: Vec<String> = vec![];
+        for name in result {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    fn send_button(
+        &mut self,
+        ui: &mut Ui,
+        id: FontId,
+        row_height: f32,
+        context: &mut RequestContext,
+    ) {
         match &self.state {
             None => {
                 // TODO width of button https://github.com/emilk/egui/blob/master/egui_demo_lib/src/demo/tests.rs
@@ -691,19 +1249,9 @@ impl Http {
                 );
 
                 if send_button.clicked() {
-                    match Url::parse(&self.url) {
-                        Ok(url) => {
-                            self.state = Some(get_request_promise(
-                                self.method.clone(),
-                                self.param_type,
-                                url,
-                                self.header.clone(),
-                                self.text_param.clone(),
-                                self.form_param.clone(),
-                            ));
-                        }
-                        Err(err) => self.state = Some(Promise::from_ready(Err(err.to_string()))),
-                    };
+                    let data = self.request_data(context);
+                    self.snapshot = Some(data.clone().resolve(context.variables).snapshot());
+                    self.state = Some(get_request_promise(data, context.variables.clone()));
                 };
             }
             Some(promise) => {
@@ -720,9 +1268,29 @@ impl Http {
                         .clicked()
                         {
                             self.state = None;
+                            self.snapshot = None;
                         }
                     }
                     Some(result) => {
+                        if let Some(snapshot) = self.snapshot.take() {
+                            context
+                                .history
+                                .push(Entry::new(self.request_name(), snapshot, result));
+                        }
+                        (self.outcomes, self.extracted) = match result {
+                            Ok(response) => (
+                                assertion::evaluate(&self.assertions, response, context.variables),
+                                extraction::extract(&self.extractions, response, context.variables),
+                            ),
+                            Err(_) => (vec![], vec![]),
+                        };
+                        for extracted in &self.extracted {
+                            if let Ok(value) = &extracted.value {
+                                context
+                                    .updates
+                                    .insert(extracted.variable.clone(), value.clone());
+                            }
+                        }
                         self.result = Some(result.clone());
                         self.state = None;
                     }
@@ -732,93 +1300,761 @@ impl Http {
     }
 }
 
-/// Create a request promise by request information
-fn get_request_promise(
+/// Information of request which is moved into request thread.
+#[derive(Clone)]
+pub struct RequestData {
+    timeouts: Timeouts,
+    proxy: Proxy,
+    tls: Tls,
+    redirect: Redirect,
+    /// Name of environment whose cookie jar is used.
+    session: String,
+    cookies: bool,
     method: Method,
     param_type: ParamType,
-    url: Url,
-    headers: Vec<(String, String)>,
+    /// Base url inherited from folders.
+    base_url: String,
+    url: String,
+    header: Vec<(String, String)>,
     text_param: String,
     form_param: Vec<(String, String, Option<PathBuf>, FormParamType)>,
-) -> Promise<RequestResult> {
-    Promise::spawn_thread(
-        String::from("slow_operation"),
-        // TODO More method request
-        move || -> RequestResult {
-            let client = reqwest::blocking::Client::new();
-
-            let mut builder = match method {
-                Method::Get => client.get(url),
-                Method::Post => client.post(url),
-                Method::Delete => client.delete(url),
-                Method::Put => client.put(url),
-                Method::Patch => client.patch(url),
-            };
-            for (k, v) in headers {
+    auth: Auth,
+}
+
+impl RequestData {
+    /// Request of GraphQL operation,`payload` is sent as json by `POST`,or as query by `GET`.
+    pub fn graphql(
+        context: &RequestContext,
+        url: &str,
+        header: &[(String, String)],
+        auth: &Auth,
+        payload: &serde_json::Map<String, serde_json::Value>,
+        get: bool,
+    ) -> Self {
+        let defaults = context.defaults;
+        let (method, param_type, text_param, form_param) = if get {
+            let query = payload
+                .iter()
+                .map(|(key, value)| {
+                    let value = match value {
+                        serde_json::Value::String(text) => text.clone(),
+                        value => value.to_string(),
+                    };
+                    (key.clone(), value, None, FormParamType::Text)
+                })
+                .collect();
+            (Method::Get, ParamType::Query, "".to_string(), query)
+        } else {
+            let body = serde_json::to_string(payload).unwrap_or_default();
+            (Method::Post, ParamType::Json, body, vec![])
+        };
+        Self {
+            timeouts: context.settings.timeouts,
+            proxy: context.settings.proxy.clone(),
+            tls: context.settings.tls.clone(),
+            redirect: Default::default(),
+            session: context.session.to_string(),
+            cookies: true,
+            method,
+            param_type,
+            base_url: defaults.base_url.clone(),
+            url: url.to_string(),
+            header: defaults.merge_header(header),
+            text_param,
+            form_param,
+            auth: auth.clone(),
+        }
+    }
+
+    /// Resolve all `{{name}}` placeholders by variables.
+    pub fn resolve(self, variables: &Variables) -> Self {
+        let substitute = |text: &String| environment::substitute(text, variables);
+        Self {
+            url: collection::join_url(&substitute(&self.base_url), &substitute(&self.url)),
+            header: self
+                .header
+                .iter()
+                .map(|(k, v)| (substitute(k), substitute(v)))
+                .collect(),
+            text_param: substitute(&self.text_param),
+            form_param: self
+                .form_param
+                .iter()
+                .map(|(k, v, path, typ)| (substitute(k), substitute(v), path.clone(), typ.clone()))
+                .collect(),
+            auth: self.auth.resolve(variables),
+            ..self
+        }
+    }
+
+    /// Record of request,should be called after resolving.
+    pub fn snapshot(&self) -> Snapshot {
+        let mut url = self.url.clone();
+        if self.param_type == ParamType::Query && !self.form_param.is_empty() {
+            if let Ok(mut parsed) = Url::parse(&url) {
+                parsed
+                    .query_pairs_mut()
+                    .extend_pairs(self.form_param.iter().map(|(k, v, ..)| (k, v)));
+                url = parsed.to_string();
+            }
+        }
+        let mut header = self.header.clone();
+        let content_type = self.param_type.get_content_type();
+        if !content_type.is_empty() {
+            header.retain(|(key, _)| !key.eq_ignore_ascii_case("content-type"));
+            header.push(("Content-Type".to_string(), content_type));
+        }
+        Snapshot {
+            method: self.method.as_str().to_string(),
+            url,
+            header,
+            body: match self.param_type {
+                ParamType::Json | ParamType::Other => self.text_param.clone(),
+                _ => "".to_string(),
+            },
+            form: match self.param_type {
+                ParamType::FormData => self
+                    .form_param
+                    .iter()
+                    .map(|(k, v, path, typ)| match typ {
+                        FormParamType::File => (k.clone(), "".to_string(), path.clone()),
+                        FormParamType::Text => (k.clone(), v.clone(), None),
+                    })
+                    .collect(),
+                _ => vec![],
+            },
+            auth: self.auth.clone(),
+        }
+    }
+
+    /// Build request,it may be called more than once such as retrying with digest authorization.
+    /// Request of a redirect is built by `follow`,whose url has no query params.
+    fn build(
+        &self,
+        client: &reqwest::Client,
+        url: &Url,
+        follow: Option<&Follow>,
+    ) -> Result<reqwest::RequestBuilder, String> {
+        let method = match follow {
+            Some(follow) => follow.method.clone(),
+            None => self.reqwest_method()?,
+        };
+        let body = follow.is_none_or(|follow| follow.body);
+        let credentials = follow.is_none_or(|follow| follow.credentials);
+        let mut builder = client.request(method, url.clone());
+        for (k, v) in &self.header {
+            let skipped = (!credentials
+                && (k.eq_ignore_ascii_case("authorization") || k.eq_ignore_ascii_case("cookie")))
+                || (!body && k.eq_ignore_ascii_case("content-type"));
+            if !skipped {
                 builder = builder.header(k, v);
             }
-            builder = match param_type {
-                ParamType::FormData => {
-                    let mut form = multipart::Form::new();
-                    for (k, v_text, v_file, typ) in form_param {
-                        match (typ, v_file) {
-                            (FormParamType::File, Some(v_file)) => {
-                                form = match form.file(k, v_file) {
-                                    Ok(file) => file,
-                                    Err(err) => return Err(format!("{}", err)),
-                                };
-                            }
-                            (FormParamType::Text, _) => {
-                                form = form.text(k, v_text);
+        }
+        builder = match self.param_type {
+            _ if !body => builder,
+            ParamType::Query if follow.is_some() => builder,
+            ParamType::FormData => {
+                let mut form = multipart::Form::new();
+                for (k, v_text, v_file, typ) in &self.form_param {
+                    match (typ, v_file) {
+                        (FormParamType::File, Some(v_file)) => {
+                            let bytes = match std::fs::read(v_file) {
+                                Ok(bytes) => bytes,
+                                Err(err) => return Err(format!("{}", err)),
+                            };
+                            let mut part = multipart::Part::bytes(bytes);
+                            if let Some(name) = v_file.file_name() {
+                                part = part.file_name(name.to_string_lossy().to_string());
                             }
-                            _ => {}
+                            let mime = mime_guess::from_path(v_file).first_or_octet_stream();
+                            part = part
+                                .mime_str(mime.as_ref())
+                                .map_err(|err| err.to_string())?;
+                            form = form.part(k.clone(), part);
+                        }
+                        (FormParamType::Text, _) => {
+                            form = form.text(k.clone(), v_text.clone());
                         }
+                        _ => {}
                     }
-                    builder.multipart(form)
                 }
-                ParamType::Json => builder.body(text_param),
-                ParamType::Other => builder.body(text_param),
-                ParamType::Query => builder.query(
-                    &form_param
-                        .iter()
-                        .map(|(k, v, ..)| (k, v))
-                        .collect::<Vec<(&String, &String)>>(),
-                ),
-                _ => builder,
-            };
-
-            // Override content-type if not empty.Maybe add a `override` button for user to select override or not.
-            let content_type = param_type.get_content_type();
-            if !content_type.is_empty() {
-                builder = builder.header("Content-Type", content_type);
+                builder.multipart(form)
             }
+            ParamType::Json => builder.body(self.text_param.clone()),
+            ParamType::Other => builder.body(self.text_param.clone()),
+            ParamType::Query => builder.query(
+                &self
+                    .form_param
+                    .iter()
+                    .map(|(k, v, ..)| (k, v))
+                    .collect::<Vec<(&String, &String)>>(),
+            ),
+            _ => builder,
+        };
 
-            // .query(&query_param)
-            let result = builder.send();
+        // Override content-type if not empty.Maybe add a `override` button for user to select override or not.
+        let content_type = self.param_type.get_content_type();
+        if !content_type.is_empty() && body {
+            builder = builder.header("Content-Type", content_type);
+        }
+        match credentials {
+            true => Ok(self.auth.apply(builder)),
+            false => Ok(builder),
+        }
+    }
 
-            return match result {
-                Ok(result) => Result::Ok(Response {
-                    code: result.status(),
-                    size: result.content_length(),
-                    body: result.text().unwrap_or_else(|_| "".to_string()),
-                }),
-                Err(err) => Err(format!("{}", err)),
+    fn reqwest_method(&self) -> Result<reqwest::Method, String> {
+        reqwest::Method::from_bytes(self.method.as_str().as_bytes())
+            .map_err(|_| format!("Invalid method: {}", self.method.as_str()))
+    }
+}
+
+/// Runtime which requests are sent on,a transfer of async client can be stopped by dropping it.
+fn runtime() -> &'static Runtime {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .expect("Failed to create runtime")
+    })
+}
+
+/// Create a request promise by request information,`{{name}}` placeholders are resolved by `variables` before sending.
+pub fn get_request_promise(data: RequestData, variables: Variables) -> Sending {
+    let cancel = Arc::new(Notify::new());
+    let canceled = cancel.clone();
+    let promise = Promise::spawn_thread(
+        String::from("slow_operation"),
+        // The transfer is async,the thread only blocks on it so ui keeps polling the promise.
+        move || -> RequestResult {
+            let mut data = data.resolve(&variables);
+            let url = match Url::parse(&data.url) {
+                Ok(url) => url,
+                Err(err) => return Err(err.to_string().into()),
             };
+
+            runtime().block_on(async {
+                let send = async {
+                    data.authorize().await?;
+                    data.send(url).await
+                };
+                match future::select(Box::pin(send), Box::pin(canceled.notified())).await {
+                    Either::Left((result, _)) => result,
+                    // Dropping the future aborts the transfer.
+                    Either::Right(_) => Err("Canceled".to_string().into()),
+                }
+            })
         },
-    )
+    );
+    Sending { promise, cancel }
 }
 
-#[derive(Deserialize, Serialize, Eq, PartialEq, Debug, Clone)]
-enum Method {
+impl RequestData {
+    /// Options of client which sends request to url.
+    fn client_options(&self, url: &Url) -> ClientOptions {
+        ClientOptions {
+            proxy: self.proxy.effective(),
+            insecure: self.tls.insecure,
+            ca_files: self.tls.ca_files.clone(),
+            identity: url
+                .host_str()
+                .and_then(|host| self.tls.identity(host))
+                .cloned(),
+            session: self.session.clone(),
+            cookies: self.cookies,
+            connect_timeout: self.timeouts.connect(),
+        }
+    }
+
+    /// Replace OAuth2 by bearer token,which is fetched with the same options as request.
+    async fn authorize(&mut self) -> Result<(), String> {
+        if let Auth::OAuth2(oauth2) = &self.auth {
+            let token_url = Url::parse(&oauth2.token_url)
+                .map_err(|err| format!("Invalid OAuth2 token url: {}", err))?;
+            let client = TokenClient {
+                client: client::client(&self.client_options(&token_url))?,
+                timeout: self.timeouts.total(),
+            };
+            self.auth = Auth::Bearer {
+                token: oauth2.access_token(&client).await?,
+            };
+        }
+        Ok(())
+    }
+
+    async fn send(&self, url: Url) -> RequestResult {
+        let options = self.client_options(&url);
+        let proxy = options.proxy.clone();
+        let client = client::client(&options)?;
+        match self.timeouts.total() {
+            None => self.transfer(&client, url, &proxy).await,
+            Some(total) => {
+                match tokio::time::timeout(total, self.transfer(&client, url, &proxy)).await {
+                    Ok(result) => result,
+                    Err(_) => Err(RequestError::Timeout(format!(
+                        "No complete response in {} s",
+                        total.as_secs()
+                    ))),
+                }
+            }
+        }
+    }
+
+    async fn transfer(&self, client: &reqwest::Client, url: Url, proxy: &Proxy) -> RequestResult {
+        let start = Instant::now();
+        let mut result = self.build(client, &url, None)?.send().await;
+
+        // Answer the challenge of digest authorization.
+        if let (Auth::Digest { username, password }, Ok(response)) = (&self.auth, &result) {
+            let challenge = auth::digest_challenge(
+                response
+                    .headers()
+                    .get_all(WWW_AUTHENTICATE)
+                    .iter()
+                    .filter_map(|value| value.to_str().ok()),
+            );
+            if let (StatusCode::UNAUTHORIZED, Some(challenge)) = (response.status(), challenge) {
+                // Uri is hashed after query params are added to request.
+                let mut request = self
+                    .build(client, &url, None)?
+                    .build()
+                    .map_err(|err| err.to_string())?;
+                let authorization = auth::digest_authorization(
+                    &challenge,
+                    self.method.as_str(),
+                    request.url(),
+                    username,
+                    password,
+                )?;
+                request.headers_mut().insert(
+                    AUTHORIZATION,
+                    HeaderValue::from_str(&authorization).map_err(|err| err.to_string())?,
+                );
+                result = client.execute(request).await;
+            }
+        }
+
+        let mut result = result?;
+        let original = url.clone();
+        let mut url = url;
+        let mut method = self.reqwest_method()?;
+        let mut body = true;
+        let mut redirects = vec![];
+        while let Some((location, next, mut follow)) =
+            self.redirect_of(&result, &url, &original, &method, redirects.len())
+        {
+            follow.body &= body;
+            redirects.push(Hop {
+                method: method.to_string(),
+                url: url.to_string(),
+                status: result.status(),
+                location,
+                headers: header_pairs(result.headers()),
+                elapsed: start.elapsed(),
+            });
+            body = follow.body;
+            method = follow.method.clone();
+            url = next;
+            result = self.build(client, &url, Some(&follow))?.send().await?;
+        }
+
+        let waiting = start.elapsed();
+        let code = result.status();
+        let size = result.content_length();
+        let version = result.version();
+        let remote_addr = result.remote_addr();
+        let tls = match url.scheme() {
+            "https" => TlsInfo::of(&result),
+            _ => None,
+        };
+        let proxy = proxy.describe(&url);
+        let headers = header_pairs(result.headers());
+        let content_type = headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case("content-type"))
+            .map(|(_, v)| v.to_string());
+        // Body of event stream never ends,events are read in background instead.
+        let is_event_stream = content_type
+            .as_deref()
+            .is_some_and(|content_type| content_type.starts_with("text/event-stream"));
+        let (bytes, events) = if is_event_stream {
+            (vec![], Some(EventStream::open(result)))
+        } else {
+            (result.bytes().await?.to_vec(), None)
+        };
+        let (kind, body) = body::decode(content_type.as_deref(), &bytes);
+        let image = (kind == BodyKind::Image).then(|| Image::load(&bytes));
+        let json = json::parse(content_type.as_deref(), &body);
+        let pretty = json.as_ref().and_then(|_| json::beautify(&body).ok());
+        Result::Ok(Response {
+            json,
+            pretty,
+            events,
+            body,
+            bytes,
+            kind,
+            image,
+            size,
+            code,
+            headers,
+            version,
+            remote_addr,
+            proxy,
+            tls,
+            redirects,
+            url: url.to_string(),
+            waiting,
+            elapsed: start.elapsed(),
+        })
+    }
+
+    /// Location and next request of a redirect response which should be followed.
+    fn redirect_of(
+        &self,
+        response: &reqwest::Response,
+        url: &Url,
+        original: &Url,
+        method: &reqwest::Method,
+        hops: usize,
+    ) -> Option<(String, Url, Follow)> {
+        let location = response.headers().get(LOCATION)?;
+        let location = String::from_utf8_lossy(location.as_bytes()).to_string();
+        let next = url.join(&location).ok()?;
+        let follow = self
+            .redirect
+            .follow(method, response.status(), original, &next, hops)?;
+        Some((location, next, follow))
+    }
+}
+
+fn header_pairs(headers: &reqwest::header::HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(k, v)| {
+            (
+                k.to_string(),
+                String::from_utf8_lossy(v.as_bytes()).to_string(),
+            )
+        })
+        .collect()
+}
+
+#[derive(Deserialize, Serialize, Eq, PartialEq, Debug, Clone, Default)]
+pub enum Method {
     Post,
+    #[default]
     Get,
     Put,
     Delete,
     Patch,
+    Head,
+    Options,
+    Trace,
+    Connect,
+    /// Methods out of standard,such as `PROPFIND` and `PURGE`.
+    Custom(String),
 }
 
-impl Default for Method {
-    fn default() -> Self {
-        Self::Get
+impl Method {
+    /// Methods listed in method select.
+    const PRESETS: [Method; 9] = [
+        Method::Get,
+        Method::Post,
+        Method::Put,
+        Method::Delete,
+        Method::Patch,
+        Method::Head,
+        Method::Options,
+        Method::Trace,
+        Method::Connect,
+    ];
+
+    fn as_str(&self) -> &str {
+        match self {
+            Method::Post => "POST",
+            Method::Get => "GET",
+            Method::Put => "PUT",
+            Method::Delete => "DELETE",
+            Method::Patch => "PATCH",
+            Method::Head => "HEAD",
+            Method::Options => "OPTIONS",
+            Method::Trace => "TRACE",
+            Method::Connect => "CONNECT",
+            Method::Custom(method) => method.as_str(),
+        }
+    }
+
+    fn label(&self) -> &str {
+        match self {
+            Method::Post => "Post",
+            Method::Get => "Get",
+            Method::Put => "Put",
+            Method::Delete => "Delete",
+            Method::Patch => "Patch",
+            Method::Head => "Head",
+            Method::Options => "Options",
+            Method::Trace => "Trace",
+            Method::Connect => "Connect",
+            Method::Custom(_) => "Custom",
+        }
+    }
+
+    /// Parse method by name,return `None` if the name isn't a valid http method token.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let upper = name.to_uppercase();
+        match Method::PRESETS
+            .into_iter()
+            .find(|method| method.as_str() == upper)
+        {
+            Some(method) => Some(method),
+            None => match reqwest::Method::from_bytes(name.as_bytes()) {
+                Ok(_) if !name.is_empty() => Some(Method::Custom(name.to_string())),
+                _ => None,
+            },
+        }
     }
+
+    /// Methods which only send params by query.
+    pub fn is_query_only(&self) -> bool {
+        matches!(self, Method::Get | Method::Head)
+    }
+}
+
+#[test]
+fn test_curl_round_trip() {
+    let check = |http: Http| {
+        let curl = http.to_curl(&Defaults::default(), &Variables::default());
+        let parsed = match curl::parse_curl(&curl) {
+            Ok(parsed) => parsed,
+            Err(err) => panic!("{}: {}", err, curl),
+        };
+        assert_eq!(parsed.url, http.url, "{}", curl);
+        assert_eq!(parsed.method, http.method, "{}", curl);
+        assert_eq!(parsed.param_type, http.param_type, "{}", curl);
+        assert_eq!(parsed.text_param, http.text_param, "{}", curl);
+        assert_eq!(parsed.form_param, http.form_param, "{}", curl);
+        assert_eq!(parsed.auth, http.auth, "{}", curl);
+        assert_eq!(parsed.tls, http.tls, "{}", curl);
+        assert_eq!(parsed.redirect.follow, http.redirect.follow, "{}", curl);
+        assert_eq!(parsed.redirect.max, http.redirect.max, "{}", curl);
+        for header in &http.header {
+            assert!(parsed.header.contains(header), "{}", curl);
+        }
+    };
+
+    check(Http {
+        url: "http://localhost/users".to_string(),
+        method: Method::Get,
+        header: vec![("Authorization".to_string(), "Bearer it's".to_string())],
+        form_param: vec![
+            (
+                "name".to_string(),
+                "a b&c".to_string(),
+                None,
+                FormParamType::Text,
+            ),
+            (
+                "page".to_string(),
+                "1".to_string(),
+                None,
+                FormParamType::Text,
+            ),
+        ],
+        param_type: ParamType::Query,
+        ..Default::default()
+    });
+    check(Http {
+        url: "http://localhost/login".to_string(),
+        method: Method::Post,
+        header: vec![("Content-Type".to_string(), "application/json".to_string())],
+        text_param: r#"{"username":"it's me","password":"$HOME \\ `x`"}"#.to_string(),
+        param_type: ParamType::Json,
+        ..Default::default()
+    });
+    check(Http {
+        url: "https://dev.local:8443/secure".to_string(),
+        tls: Tls {
+            insecure: true,
+            ca_files: vec![PathBuf::from("certs/dev ca.pem")],
+            identities: vec![crate::request::tls::ClientIdentity {
+                host: "dev.local".to_string(),
+                cert: PathBuf::from("client.p12"),
+                key: None,
+                password: "pass:word".to_string(),
+            }],
+        },
+        redirect: Redirect {
+            follow: false,
+            ..Default::default()
+        },
+        param_type: ParamType::Query,
+        ..Default::default()
+    });
+    check(Http {
+        url: "http://localhost/secret".to_string(),
+        auth: Auth::Basic {
+            username: "admin".to_string(),
+            password: "p@ss:word".to_string(),
+        },
+        param_type: ParamType::Query,
+        ..Default::default()
+    });
+    check(Http {
+        url: "http://localhost/dav".to_string(),
+        method: Method::Custom("PROPFIND".to_string()),
+        header: vec![("Depth".to_string(), "1".to_string())],
+        param_type: ParamType::Other,
+        ..Default::default()
+    });
+    check(Http {
+        url: "http://localhost/".to_string(),
+        method: Method::Head,
+        param_type: ParamType::Query,
+        ..Default::default()
+    });
+    check(Http {
+        url: "http://localhost/upload".to_string(),
+        method: Method::Put,
+        form_param: vec![
+            (
+                "name".to_string(),
+                "a;b".to_string(),
+                None,
+                FormParamType::Text,
+            ),
+            (
+                "mail".to_string(),
+                "@me".to_string(),
+                None,
+                FormParamType::Text,
+            ),
+            (
+                "file".to_string(),
+                "".to_string(),
+                Some(PathBuf::from("/tmp/my file.txt")),
+                FormParamType::File,
+            ),
+        ],
+        param_type: ParamType::FormData,
+        ..Default::default()
+    });
+}
+
+#[test]
+fn test_timeout_and_cancel() {
+    use std::io::Read;
+    use std::time::Duration;
+
+    // Server which accepts connections but never responds.
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    let (closed, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let closed = closed.clone();
+            std::thread::spawn(move || {
+                let mut buf = [0; 1024];
+                let mut stream = stream.unwrap();
+                while matches!(stream.read(&mut buf), Ok(n) if n > 0) {}
+                let _ = closed.send(());
+            });
+        }
+    });
+    let data = |total: u64| RequestData {
+        timeouts: Timeouts { connect: 1, total },
+        proxy: Default::default(),
+        tls: Default::default(),
+        redirect: Default::default(),
+        session: "".to_string(),
+        cookies: false,
+        method: Method::Get,
+        param_type: ParamType::None,
+        base_url: "".to_string(),
+        url: url.clone(),
+        header: vec![],
+        text_param: "".to_string(),
+        form_param: vec![],
+        auth: Auth::None,
+    };
+
+    let sending = get_request_promise(data(1), Variables::default());
+    assert!(matches!(
+        sending.block_until_ready(),
+        Err(RequestError::Timeout(_))
+    ));
+    receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+
+    let sending = get_request_promise(data(0), Variables::default());
+    std::thread::sleep(Duration::from_millis(300));
+    assert!(sending.ready().is_none());
+    drop(sending);
+    receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+}
+
+#[test]
+fn test_digest_with_query() {
+    use std::io::{BufRead, BufReader, Write};
+
+    // Server which accepts digest authorization only if its uri is the request target.
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/items?page=1", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut target = "".to_string();
+            let mut uri = None;
+            let mut line = "".to_string();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                if target.is_empty() {
+                    target = line.split(' ').nth(1).unwrap().to_string();
+                } else if let Some(value) = line.strip_prefix("authorization: ") {
+                    uri = value
+                        .split(", ")
+                        .find_map(|param| param.strip_prefix("uri=\""))
+                        .map(|uri| uri.trim_end_matches('"').to_string());
+                }
+                line.clear();
+            }
+            let response = match uri {
+                None => "HTTP/1.1 401 Unauthorized\r\nWWW-Authenticate: Digest realm=\"r\", nonce=\"n\", qop=\"auth\"\r\n",
+                Some(uri) if uri == target => "HTTP/1.1 200 OK\r\n",
+                Some(_) => "HTTP/1.1 403 Forbidden\r\n",
+            };
+            let _ = write!(stream, "{}Content-Length: 0\r\n\r\n", response);
+        }
+    });
+    let data = RequestData {
+        timeouts: Timeouts {
+            connect: 5,
+            total: 5,
+        },
+        proxy: Default::default(),
+        tls: Default::default(),
+        redirect: Default::default(),
+        session: "".to_string(),
+        cookies: false,
+        method: Method::Get,
+        param_type: ParamType::Query,
+        base_url: "".to_string(),
+        url,
+        header: vec![],
+        text_param: "".to_string(),
+        form_param: vec![(
+            "q".to_string(),
+            "a b".to_string(),
+            None,
+            FormParamType::Text,
+        )],
+        auth: Auth::Digest {
+            username: "user".to_string(),
+            password: "pass".to_string(),
+        },
+    };
+    let sending = get_request_promise(data, Variables::default());
+    assert!(matches!(
+        sending.block_until_ready(),
+        Ok(response) if response.code == StatusCode::OK
+    ));
 }
@@ -0,0 +1,357 @@
+use eframe::egui::text::LayoutJob;
+use eframe::egui::{CollapsingHeader, Color32, FontId, Label, RichText, Sense, TextFormat, Ui};
+use serde_json::Value;
+
+use crate::color;
+
+/// Objects and arrays deeper than this are collapsed by default in tree view.
+const OPEN_DEPTH: usize = 2;
+
+/// Parse body as json if content type is json,or body looks like json.
+pub fn parse(content_type: Option<&str>, body: &str) -> Option<Value> {
+    let trimmed = body.trim_start();
+    let is_json = match content_type {
+        Some(content_type) if content_type.contains("json") => true,
+        _ => trimmed.starts_with('{') || trimmed.starts_with('['),
+    };
+    if !is_json {
+        return None;
+    }
+    serde_json::from_str(body).ok()
+}
+
+/// Re-indent json text.
+/// It doesn't parse the text,so `{{name}}` placeholders are kept,only brackets should be balanced.
+pub fn beautify(text: &str) -> Result<String, String> {
+    let mut result = String::with_capacity(text.len() * 2);
+    let mut depth: usize = 0;
+    let mut chars = text.chars().peekable();
+    let newline = |result: &mut String, depth: usize| {
+        result.push('\n');
+        result.push_str(&"  ".repeat(depth));
+    };
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                result.push(c);
+                while let Some(c) = chars.next() {
+                    result.push(c);
+                    match c {
+                        '\\' => result.extend(chars.next()),
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            // Placeholder,which is never valid json.
+            '{' if chars.peek() == Some(&'{') => {
+                result.push(c);
+                let mut last = c;
+                for c in chars.by_ref() {
+                    result.push(c);
+                    if last == '}' && c == '}' {
+                        break;
+                    }
+                    last = c;
+                }
+            }
+            '{' | '[' => {
+                result.push(c);
+                while chars.peek().is_some_and(|c| c.is_whitespace()) {
+                    chars.next();
+                }
+                // Keep empty object and array in one line.
+                if let Some(close @ ('}' | ']')) = chars.peek() {
+                    result.push(*close);
+                    chars.next();
+                } else {
+                    depth += 1;
+                    newline(&mut result, depth);
+                }
+            }
+            '}' | ']' => {
+                depth = match depth.checked_sub(1) {
+                    None => return Err(format!("Unexpected `{}`", c)),
+                    Some(depth) => depth,
+                };
+                newline(&mut result, depth);
+                result.push(c);
+            }
+            ',' => {
+                result.push(c);
+                newline(&mut result, depth);
+            }
+            ':' => result.push_str(": "),
+            c if c.is_whitespace() => {}
+            c => result.push(c),
+        }
+    }
+    if depth > 0 {
+        return Err("Unclosed object or array".to_string());
+    }
+    Ok(result)
+}
+
+/// Layout json text with keys,strings and literals highlighted.
+pub fn highlight(text: &str, font_id: FontId, wrap_width: f32) -> LayoutJob {
+    let mut job = LayoutJob::default();
+    let format = |color: Color32| TextFormat::simple(font_id.clone(), color);
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let (len, color) = match c {
+            '"' => {
+                let len = string_len(rest);
+                // A string followed by `:` is a key.
+                let is_key = rest[len..].trim_start().starts_with(':');
+                (
+                    len,
+                    if is_key {
+                        color::DODER_BLUE
+                    } else {
+                        color::SEA_GREEN
+                    },
+                )
+            }
+            '{' if rest.starts_with("{{") => match rest.find("}}") {
+                Some(end) => (end + 2, color::BLACK),
+                None => (2, color::BLACK),
+            },
+            '{' | '}' | '[' | ']' | ',' | ':' => (1, color::BLACK),
+            c if c.is_whitespace() => (
+                rest.find(|c: char| !c.is_whitespace())
+                    .unwrap_or(rest.len()),
+                color::BLACK,
+            ),
+            _ => (
+                rest.find(|c: char| c.is_whitespace() || ",:{}[]\"".contains(c))
+                    .unwrap_or(rest.len())
+                    .max(c.len_utf8()),
+                color::DARK_ORANGE,
+            ),
+        };
+        job.append(&rest[..len], 0.0, format(color));
+        rest = &rest[len..];
+    }
+    job.wrap.max_width = wrap_width;
+    job
+}
+
+/// Length of string token at the beginning of text,including quotes.
+fn string_len(text: &str) -> usize {
+    let mut escaped = false;
+    for (index, c) in text.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return index + 1,
+            _ => {}
+        }
+    }
+    text.len()
+}
+
+/// Collapsible tree of json value,path of node can be copied from context menu.
+pub fn tree_view(ui: &mut Ui, value: &Value) {
+    node_view(ui, None, value, "$", 0);
+}
+
+fn node_view(ui: &mut Ui, key: Option<&str>, value: &Value, path: &str, depth: usize) {
+    let children: Vec<(String, &Value, String)> = match value {
+        Value::Object(map) => map
+            .iter()
+            .map(|(key, value)| (key.clone(), value, child_path(path, key)))
+            .collect(),
+        Value::Array(array) => array
+            .iter()
+            .enumerate()
+            .map(|(index, value)| (index.to_string(), value, format!("{}[{}]", path, index)))
+            .collect(),
+        _ => {
+            let rect = ui
+                .horizontal(|ui| {
+                    if let Some(key) = key {
+                        ui.label(RichText::new(format!("{}:", key)).color(color::DODER_BLUE));
+                    }
+                    let color = match value {
+                        Value::String(_) => color::SEA_GREEN,
+                        _ => color::DARK_ORANGE,
+                    };
+                    ui.add(Label::new(RichText::new(value.to_string()).color(color)).wrap(true));
+                })
+                .response
+                .rect;
+            // Row of `horizontal` only senses hover.
+            let response = ui.interact(rect, ui.id().with(path), Sense::click());
+            copy_menu(response, value, path);
+            return;
+        }
+    };
+    let summary = match value {
+        Value::Object(_) => format!("{{{}}}", children.len()),
+        _ => format!("[{}]", children.len()),
+    };
+    let header = match key {
+        None => summary,
+        Some(key) => format!("{}: {}", key, summary),
+    };
+    let response = CollapsingHeader::new(RichText::new(header).color(color::DODER_BLUE))
+        .id_source(path)
+        .default_open(depth < OPEN_DEPTH)
+        .show(ui, |ui| {
+            for (key, value, path) in &children {
+                node_view(ui, Some(key), value, path, depth + 1);
+            }
+        });
+    copy_menu(response.header_response, value, path);
+}
+
+fn copy_menu(response: eframe::egui::Response, value: &Value, path: &str) {
+    response.context_menu(|ui| {
+        if ui.button("Copy path").clicked() {
+            ui.output().copied_text = path.to_string();
+            ui.close_menu();
+        }
+        if ui.button("Copy value").clicked() {
+            ui.output().copied_text = match value {
+                Value::String(text) => text.clone(),
+                value => serde_json::to_string_pretty(value).unwrap_or_default(),
+            };
+            ui.close_menu();
+        }
+    });
+}
+
+/// Value at path copied from tree view,such as `$.data[0]["user-name"]`,`None` if it doesn't exist.
+pub fn select<'a>(value: &'a Value, path: &str) -> Result<Option<&'a Value>, String> {
+    let invalid = || format!("Invalid json path: {}", path);
+    let mut rest = path.trim().strip_prefix('$').ok_or_else(invalid)?;
+    let mut current = value;
+    while !rest.is_empty() {
+        let next = if let Some(after) = rest.strip_prefix('.') {
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            if end == 0 {
+                return Err(invalid());
+            }
+            rest = &after[end..];
+            current.get(&after[..end])
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let end = closing_bracket(after).ok_or_else(invalid)?;
+            let index = after[..end].trim();
+            rest = &after[end + 1..];
+            if let Ok(index) = index.parse::<usize>() {
+                current.get(index)
+            } else if index.starts_with('"') {
+                let key: String = serde_json::from_str(index).map_err(|_| invalid())?;
+                current.get(key)
+            } else if let Some(key) = index
+                .strip_prefix('\'')
+                .and_then(|key| key.strip_suffix('\''))
+            {
+                current.get(key)
+            } else {
+                return Err(invalid());
+            }
+        } else {
+            return Err(invalid());
+        };
+        match next {
+            None => return Ok(None),
+            Some(next) => current = next,
+        }
+    }
+    Ok(Some(current))
+}
+
+/// Index of `]` which isn't in a quoted key.
+fn closing_bracket(text: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(_), _) if escaped => escaped = false,
+            (Some(_), '\\') => escaped = true,
+            (Some(q), c) if c == q => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, ']') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Name of json type,which is used by assertions.
+pub fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// `$.key` for identifier,otherwise `$["key"]`.
+fn child_path(path: &str, key: &str) -> String {
+    let is_identifier = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_alphanumeric() || c == '_');
+    if is_identifier {
+        format!("{}.{}", path, key)
+    } else {
+        format!("{}[{:?}]", path, key)
+    }
+}
+
+#[test]
+fn test_beautify() {
+    assert_eq!(
+        beautify(r#"{"a":1,"b":[true,null,{}],"c":"x,{y}","d":{{id}}}"#).unwrap(),
+        r#"{
+  "a": 1,
+  "b": [
+    true,
+    null,
+    {}
+  ],
+  "c": "x,{y}",
+  "d": {{id}}
+}"#
+    );
+    assert_eq!(beautify("[ ]").unwrap(), "[]");
+    assert!(beautify("{\"a\":1").is_err());
+    assert!(beautify("]").is_err());
+
+    assert_eq!(child_path("$", "name"), "$.name");
+    assert_eq!(child_path("$.a", "b c"), "$.a[\"b c\"]");
+    assert!(parse(None, " [1, 2]").is_some());
+    assert!(parse(Some("text/html"), "<html>").is_none());
+}
+
+#[test]
+fn test_select() {
+    let value: Value =
+        serde_json::from_str(r#"{"data":[{"id":7,"user-name":"a]b","tags":null}],"ok":true}"#)
+            .unwrap();
+    let select = |path: &str| select(&value, path).map(|value| value.cloned());
+    assert_eq!(select("$"), Ok(Some(value.clone())));
+    assert_eq!(select("$.data[0].id"), Ok(Some(Value::from(7))));
+    assert_eq!(
+        select(r#"$.data[0]["user-name"]"#),
+        Ok(Some(Value::from("a]b")))
+    );
+    assert_eq!(select("$.data[0]['tags']"), Ok(Some(Value::Null)));
+    assert_eq!(select("$.data[1].id"), Ok(None));
+    assert_eq!(select("$.ok.deeper"), Ok(None));
+    assert!(select("data").is_err());
+    assert!(select("$.data[0").is_err());
+    assert!(select("$..id").is_err());
+    for (key, child) in value["data"][0].as_object().unwrap() {
+        assert_eq!(
+            select(&child_path("$.data[0]", key)),
+            Ok(Some(child.clone()))
+        );
+    }
+}
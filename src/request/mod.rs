@@ -1,34 +1,73 @@
+use crate::collection::Defaults;
 use crate::components::Frame;
 use crate::egui::Color32;
+use crate::environment::Variables;
+use crate::history::History;
+use crate::setting::Settings;
 use crate::style::DEL_BTN_SIZE;
 use crate::{color, components, WeaverStyle};
 use eframe::egui;
 use eframe::egui::epaint::text::TextWrapping;
 use eframe::egui::style::Margin;
 use eframe::egui::style::TextStyle::Body;
-use eframe::egui::text::LayoutJob;
+use eframe::egui::text::{LayoutJob, TextFormat};
 use eframe::egui::{Id, ImageButton, Label, Layout, Pos2, Rect, Rounding, Sense, Stroke, Ui, Vec2};
 use std::ops::Add;
 
+pub mod assertion;
+pub mod auth;
+pub mod body;
+pub mod client;
+pub mod extraction;
+pub mod graphql;
 ///TODO  Remove pub in future.
 pub mod http;
+pub mod json;
+pub mod oauth2;
+pub mod proxy;
+pub mod redirect;
+pub mod response;
+pub mod sse;
+pub mod tls;
+pub mod websocket;
+
+/// State which requests need when drawing and sending.
+pub struct RequestContext<'a> {
+    pub variables: &'a Variables,
+    /// Settings inherited from folders.
+    pub defaults: &'a Defaults,
+    /// Finished requests are recorded here.
+    pub history: &'a mut History,
+    pub settings: &'a Settings,
+    /// Name of active environment,requests of the same session share cookies.
+    pub session: &'a str,
+    /// Values extracted from responses,they are stored into active environment after drawing.
+    pub updates: &'a mut Variables,
+}
 
 /// Request trait
 pub(crate) trait Request {
     fn request_name(&self) -> &str;
 
-    fn view(&mut self, ui: &mut Ui);
+    fn view(&mut self, ui: &mut Ui, context: &mut RequestContext);
 
     fn get_id(&self) -> String;
 
-    // TODO until ws,graphQL or rpc be supported
-    // fn request_type(&self) -> String;
+    /// Short text shown before name in request list,such as `WS`.
+    fn badge(&self) -> Option<&str> {
+        None
+    }
+
+    /// Items of context menu in request list.
+    fn context_menu(&self, _ui: &mut Ui, _defaults: &Defaults, _variables: &Variables) {}
 
     fn request_name_view(
         &self,
         is_active: bool,
         ui: &mut Ui,
         weaver_style: &WeaverStyle,
+        defaults: &Defaults,
+        variables: &Variables,
     ) -> ClickType {
         ui.style_mut().spacing.item_spacing = Vec2::new(0., 0.);
         ui.horizontal(|ui| {
@@ -45,7 +84,7 @@ pub(crate) trait Request {
                     color::WHITE
                 },
                 outer_margin: Margin::same(0.),
-                sense: Sense::click(),
+                sense: Sense::click_and_drag(),
                 ..Frame::default()
             }
             .show(ui, |ui| {
@@ -60,11 +99,18 @@ pub(crate) trait Request {
                     Some(font_id) => font_id.clone(),
                 };
 
-                let mut job = LayoutJob::simple(
-                    self.request_name().to_string(),
-                    font_id,
-                    color::BLACK,
-                    max_width - DEL_BTN_SIZE,
+                let mut job = LayoutJob::default();
+                if let Some(badge) = self.badge() {
+                    job.append(
+                        &format!("{} ", badge),
+                        0.0,
+                        TextFormat::simple(font_id.clone(), color::DODER_BLUE),
+                    );
+                }
+                job.append(
+                    self.request_name(),
+                    0.0,
+                    TextFormat::simple(font_id, color::BLACK),
                 );
                 job.wrap = TextWrapping {
                     max_rows: 1,
@@ -116,7 +162,12 @@ pub(crate) trait Request {
             if let Some(click_type) = btn_response.inner.inner {
                 return click_type;
             }
-            let frame_response = response.response;
+            let frame_response = response
+                .response
+                .context_menu(|ui| self.context_menu(ui, defaults, variables));
+            if frame_response.drag_started() {
+                return ClickType::Drag;
+            }
             let btn_rect = btn_response.response.rect;
             let label_rect = frame_response.rect;
 
@@ -160,4 +211,6 @@ pub(crate) enum ClickType {
     None,
     Delete,
     Click,
+    /// Start dragging to move request in tree.
+    Drag,
 }
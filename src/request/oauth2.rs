@@ -0,0 +1,778 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use eframe::egui::{ComboBox, DragValue, Grid, TextEdit, Ui};
+use rand::{distributions::Alphanumeric, Rng};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+
+use crate::color;
+use crate::environment::{self, Variables};
+
+/// Refresh token before it expires in this seconds.
+const EXPIRY_MARGIN: u64 = 30;
+
+/// How long to wait for browser redirecting back in authorization code flow.
+const AUTHORIZE_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug, Default)]
+pub struct OAuth2 {
+    pub grant: GrantType,
+    pub token_url: String,
+    /// Authorization endpoint,only used by authorization code flow.
+    pub auth_url: String,
+    pub client_id: String,
+    pub client_secret: String,
+    pub scope: String,
+    pub username: String,
+    pub password: String,
+    pub refresh_token: String,
+    /// Port of loopback redirect listener,`0` means a random port.
+    pub redirect_port: u16,
+    pub client_authentication: ClientAuthentication,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum GrantType {
+    #[default]
+    ClientCredentials,
+    Password,
+    RefreshToken,
+    /// Authorization code with PKCE.
+    AuthorizationCode,
+}
+
+/// How client credentials are sent to token endpoint.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ClientAuthentication {
+    #[default]
+    BasicHeader,
+    Body,
+}
+
+/// Client of request which token is fetched by.
+pub struct TokenClient {
+    pub client: reqwest::Client,
+    /// Total timeout of request,it isn't applied to waiting for browser.
+    pub timeout: Option<Duration>,
+}
+
+/// Authorization url of a flow which is waiting for browser.
+#[derive(Clone)]
+pub struct Authorizing {
+    pub url: String,
+    /// Whether browser is opened,the url should be opened by user if not.
+    pub opened: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct Token {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    /// Unix timestamp in seconds.
+    pub expires_at: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: Option<String>,
+    refresh_token: Option<String>,
+    expires_in: Option<u64>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+impl Token {
+    fn is_valid(&self) -> bool {
+        match self.expires_at {
+            None => true,
+            Some(expires_at) => expires_at > now() + EXPIRY_MARGIN,
+        }
+    }
+}
+
+/// Tokens shared by window and headless runner,key is created by `OAuth2::cache_key`.
+fn cache() -> &'static Mutex<HashMap<String, Token>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, Token>>> = OnceLock::new();
+    CACHE.get_or_init(Default::default)
+}
+
+/// Flows waiting for browser,they are shown in auth panel until finished or canceled.
+fn authorizing() -> &'static Mutex<HashMap<String, Authorizing>> {
+    static AUTHORIZING: OnceLock<Mutex<HashMap<String, Authorizing>>> = OnceLock::new();
+    AUTHORIZING.get_or_init(Default::default)
+}
+
+/// Key of a flow in `authorizing`,which is removed when it's dropped.
+struct AuthorizingGuard(String);
+
+impl Drop for AuthorizingGuard {
+    fn drop(&mut self) {
+        if let Ok(mut authorizing) = authorizing().lock() {
+            authorizing.remove(&self.0);
+        }
+    }
+}
+
+/// Urls of all flows waiting for browser.
+pub fn authorizing_urls() -> Vec<Authorizing> {
+    authorizing()
+        .lock()
+        .map(|authorizing| authorizing.values().cloned().collect())
+        .unwrap_or_default()
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+impl GrantType {
+    fn label(&self) -> &str {
+        match self {
+            GrantType::ClientCredentials => "Client Credentials",
+            GrantType::Password => "Password",
+            GrantType::RefreshToken => "Refresh Token",
+            GrantType::AuthorizationCode => "Authorization Code (PKCE)",
+        }
+    }
+}
+
+impl OAuth2 {
+    /// Secrets are hashed,token isn't reused after any of them is changed.
+    fn cache_key(&self) -> String {
+        let secrets = Sha256::digest(
+            [
+                self.client_secret.as_str(),
+                &self.password,
+                &self.refresh_token,
+            ]
+            .join("\0")
+            .as_bytes(),
+        );
+        format!(
+            "{:?}|{}|{}|{}|{}|{}|{}",
+            self.grant,
+            self.token_url,
+            self.auth_url,
+            self.client_id,
+            self.scope,
+            self.username,
+            hex::encode(secrets)
+        )
+    }
+
+    pub fn cached_token(&self) -> Option<Token> {
+        cache().lock().ok()?.get(&self.cache_key()).cloned()
+    }
+
+    pub fn authorizing(&self) -> Option<Authorizing> {
+        authorizing().lock().ok()?.get(&self.cache_key()).cloned()
+    }
+
+    pub fn clear_token(&self) {
+        if let Ok(mut cache) = cache().lock() {
+            cache.remove(&self.cache_key());
+        }
+    }
+
+    pub fn view(&mut self, ui: &mut Ui, variables: &Variables) {
+        let width = ui.available_width() / 2.0;
+        Grid::new("oauth2_grid").num_columns(2).show(ui, |ui| {
+            ui.label("Grant type");
+            ComboBox::from_id_source("oauth2_grant_combo_box")
+                .selected_text(self.grant.label().to_string())
+                .show_ui(ui, |ui| {
+                    for grant in [
+                        GrantType::ClientCredentials,
+                        GrantType::Password,
+                        GrantType::RefreshToken,
+                        GrantType::AuthorizationCode,
+                    ] {
+                        ui.selectable_value(&mut self.grant, grant, grant.label());
+                    }
+                });
+            ui.end_row();
+            if self.grant == GrantType::AuthorizationCode {
+                ui.label("Auth URL");
+                environment::variable_text_edit(ui, &mut self.auth_url, variables, width);
+                ui.end_row();
+                ui.label("Redirect port");
+                ui.horizontal(|ui| {
+                    ui.add(DragValue::new(&mut self.redirect_port));
+                    ui.label(format!(
+                        "http://127.0.0.1:{}/callback",
+                        match self.redirect_port {
+                            0 => "<random>".to_string(),
+                            port => port.to_string(),
+                        }
+                    ));
+                });
+                ui.end_row();
+            }
+            ui.label("Token URL");
+            environment::variable_text_edit(ui, &mut self.token_url, variables, width);
+            ui.end_row();
+            ui.label("Client ID");
+            environment::variable_text_edit(ui, &mut self.client_id, variables, width);
+            ui.end_row();
+            ui.label("Client secret");
+            ui.add(
+                TextEdit::singleline(&mut self.client_secret)
+                    .password(true)
+                    .desired_width(width),
+            );
+            ui.end_row();
+            ui.label("Send client credentials");
+            ui.horizontal(|ui| {
+                ui.selectable_value(
+                    &mut self.client_authentication,
+                    ClientAuthentication::BasicHeader,
+                    "Basic header",
+                );
+                ui.selectable_value(
+                    &mut self.client_authentication,
+                    ClientAuthentication::Body,
+                    "In body",
+                );
+            });
+            ui.end_row();
+            ui.label("Scope");
+            environment::variable_text_edit(ui, &mut self.scope, variables, width);
+            ui.end_row();
+            match self.grant {
+                GrantType::Password => {
+                    ui.label("Username");
+                    environment::variable_text_edit(ui, &mut self.username, variables, width);
+                    ui.end_row();
+                    ui.label("Password");
+                    ui.add(
+                        TextEdit::singleline(&mut self.password)
+                            .password(true)
+                            .desired_width(width),
+                    );
+                    ui.end_row();
+                }
+                GrantType::RefreshToken => {
+                    ui.label("Refresh token");
+                    environment::variable_text_edit(ui, &mut self.refresh_token, variables, width);
+                    ui.end_row();
+                }
+                _ => {}
+            }
+            ui.label("Token");
+            // Key of cache is made from resolved config.
+            let resolved = self.resolve(variables);
+            ui.horizontal(|ui| {
+                match resolved.cached_token() {
+                    None => ui.label("Fetched when sending"),
+                    Some(token) => ui.label(match token.expires_at {
+                        None => "Cached,never expires".to_string(),
+                        Some(expires_at) if expires_at > now() => {
+                            format!("Cached,expires in {} s", expires_at - now())
+                        }
+                        Some(_) => "Expired".to_string(),
+                    }),
+                };
+                if ui.button("Clear").clicked() {
+                    resolved.clear_token();
+                }
+            });
+            ui.end_row();
+            if let Some(authorizing) = resolved.authorizing() {
+                ui.label("Authorize");
+                ui.vertical(|ui| {
+                    match authorizing.opened {
+                        true => ui.label("Waiting for authorization in browser"),
+                        false => ui.colored_label(
+                            color::DARK_ORANGE,
+                            "Browser can't be opened,open this url to authorize",
+                        ),
+                    };
+                    ui.horizontal(|ui| {
+                        ui.hyperlink_to("Open", &authorizing.url);
+                        if ui.button("Copy url").clicked() {
+                            ui.output().copied_text = authorizing.url.clone();
+                        }
+                    });
+                });
+                ui.end_row();
+            }
+        });
+    }
+
+    pub fn texts(&self) -> Vec<&String> {
+        vec![
+            &self.token_url,
+            &self.auth_url,
+            &self.client_id,
+            &self.client_secret,
+            &self.scope,
+            &self.username,
+            &self.password,
+            &self.refresh_token,
+        ]
+    }
+
+    pub fn resolve(&self, variables: &Variables) -> Self {
+        let substitute = |text: &String| environment::substitute(text, variables);
+        Self {
+            token_url: substitute(&self.token_url),
+            auth_url: substitute(&self.auth_url),
+            client_id: substitute(&self.client_id),
+            client_secret: substitute(&self.client_secret),
+            scope: substitute(&self.scope),
+            username: substitute(&self.username),
+            password: substitute(&self.password),
+            refresh_token: substitute(&self.refresh_token),
+            ..self.clone()
+        }
+    }
+
+    /// Get access token from cache,refresh or fetch a new one if it's expired.
+    pub async fn access_token(&self, client: &TokenClient) -> Result<String, String> {
+        self.access_token_with(client, |url| webbrowser::open(url).is_ok())
+            .await
+    }
+
+    /// Same as `access_token`,`open_browser` is called with the authorization url and tells whether it's opened.
+    pub async fn access_token_with(
+        &self,
+        client: &TokenClient,
+        open_browser: impl FnOnce(&str) -> bool,
+    ) -> Result<String, String> {
+        let cached = self.cached_token();
+        if let Some(token) = &cached {
+            if token.is_valid() {
+                return Ok(token.access_token.clone());
+            }
+        }
+        let refresh_token = cached
+            .and_then(|token| token.refresh_token)
+            .filter(|token| !token.is_empty());
+        let token = match refresh_token {
+            // Fetch a new token if refreshing failed.
+            Some(refresh_token) => match self.refresh(client, &refresh_token).await {
+                Ok(token) => token,
+                Err(_) => self.fetch(client, open_browser).await?,
+            },
+            None => self.fetch(client, open_browser).await?,
+        };
+        let access_token = token.access_token.clone();
+        if let Ok(mut cache) = cache().lock() {
+            cache.insert(self.cache_key(), token);
+        }
+        Ok(access_token)
+    }
+
+    async fn fetch(
+        &self,
+        client: &TokenClient,
+        open_browser: impl FnOnce(&str) -> bool,
+    ) -> Result<Token, String> {
+        match self.grant {
+            GrantType::ClientCredentials => {
+                self.token_request(
+                    client,
+                    vec![("grant_type", "client_credentials".to_string())],
+                )
+                .await
+            }
+            GrantType::Password => {
+                self.token_request(
+                    client,
+                    vec![
+                        ("grant_type", "password".to_string()),
+                        ("username", self.username.clone()),
+                        ("password", self.password.clone()),
+                    ],
+                )
+                .await
+            }
+            GrantType::RefreshToken => self.refresh(client, &self.refresh_token).await,
+            GrantType::AuthorizationCode => self.authorization_code(client, open_browser).await,
+        }
+    }
+
+    async fn refresh(&self, client: &TokenClient, refresh_token: &str) -> Result<Token, String> {
+        let mut token = self
+            .token_request(
+                client,
+                vec![
+                    ("grant_type", "refresh_token".to_string()),
+                    ("refresh_token", refresh_token.to_string()),
+                ],
+            )
+            .await?;
+        // Server may not issue a new refresh token.
+        if token.refresh_token.is_none() {
+            token.refresh_token = Some(refresh_token.to_string());
+        }
+        Ok(token)
+    }
+
+    async fn authorization_code(
+        &self,
+        client: &TokenClient,
+        open_browser: impl FnOnce(&str) -> bool,
+    ) -> Result<Token, String> {
+        let listener = TcpListener::bind(("127.0.0.1", self.redirect_port))
+            .await
+            .map_err(|err| format!("Failed to listen redirect port: {}", err))?;
+        let port = listener.local_addr().map_err(|err| err.to_string())?.port();
+        let redirect_uri = format!("http://127.0.0.1:{}/callback", port);
+
+        let verifier = random_string(64);
+        let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+        let state = random_string(16);
+
+        let mut auth_url = Url::parse(&self.auth_url).map_err(|err| err.to_string())?;
+        auth_url
+            .query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &self.client_id)
+            .append_pair("redirect_uri", &redirect_uri)
+            .append_pair("state", &state)
+            .append_pair("code_challenge", &challenge)
+            .append_pair("code_challenge_method", "S256");
+        if !self.scope.is_empty() {
+            auth_url.query_pairs_mut().append_pair("scope", &self.scope);
+        }
+        // Url is shown in auth panel until the flow is finished or canceled.
+        let key = self.cache_key();
+        let _guard = AuthorizingGuard(key.clone());
+        let opened = open_browser(auth_url.as_str());
+        if let Ok(mut authorizing) = authorizing().lock() {
+            authorizing.insert(
+                key,
+                Authorizing {
+                    url: auth_url.to_string(),
+                    opened,
+                },
+            );
+        }
+
+        let code = wait_for_code(listener, &state).await?;
+        self.token_request(
+            client,
+            vec![
+                ("grant_type", "authorization_code".to_string()),
+                ("code", code),
+                ("redirect_uri", redirect_uri),
+                ("code_verifier", verifier),
+            ],
+        )
+        .await
+    }
+
+    async fn token_request(
+        &self,
+        client: &TokenClient,
+        mut form: Vec<(&str, String)>,
+    ) -> Result<Token, String> {
+        if !self.scope.is_empty() {
+            form.push(("scope", self.scope.clone()));
+        }
+        let mut builder = client.client.post(&self.token_url);
+        if let Some(timeout) = client.timeout {
+            builder = builder.timeout(timeout);
+        }
+        match self.client_authentication {
+            ClientAuthentication::BasicHeader if !self.client_secret.is_empty() => {
+                builder = builder.basic_auth(&self.client_id, Some(&self.client_secret));
+            }
+            _ => {
+                form.push(("client_id", self.client_id.clone()));
+                if !self.client_secret.is_empty() {
+                    form.push(("client_secret", self.client_secret.clone()));
+                }
+            }
+        }
+        let response = builder
+            .header("Accept", "application/json")
+            .form(&form)
+            .send()
+            .await
+            .map_err(|err| format!("Failed to fetch OAuth2 token: {}", err))?;
+        let status = response.status();
+        let body = response.text().await.map_err(|err| err.to_string())?;
+        let token: TokenResponse = serde_json::from_str(&body)
+            .map_err(|_| format!("Failed to fetch OAuth2 token: {} {}", status, body))?;
+        match (token.access_token, token.error) {
+            (Some(access_token), None) if status.is_success() => Ok(Token {
+                access_token,
+                refresh_token: token.refresh_token,
+                expires_at: token.expires_in.map(|expires_in| now() + expires_in),
+            }),
+            (_, error) => Err(format!(
+                "Failed to fetch OAuth2 token: {} {} {}",
+                status,
+                error.unwrap_or_default(),
+                token.error_description.unwrap_or_default()
+            )),
+        }
+    }
+}
+
+fn random_string(len: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+/// Accept the redirection of browser and return the authorization code,
+/// listener is closed when it's returned or dropped.
+async fn wait_for_code(listener: TcpListener, state: &str) -> Result<String, String> {
+    let accept = async {
+        loop {
+            let (mut stream, _) = listener.accept().await.map_err(|err| err.to_string())?;
+            let mut request_line = String::new();
+            BufReader::new(&mut stream)
+                .read_line(&mut request_line)
+                .await
+                .map_err(|err| err.to_string())?;
+            // `GET /callback?code=xxx&state=xxx HTTP/1.1`
+            let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+            let url =
+                Url::parse(&format!("http://127.0.0.1{}", path)).map_err(|err| err.to_string())?;
+            let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+            if !params.contains_key("code") && !params.contains_key("error") {
+                // Such as `/favicon.ico`
+                let _ = stream
+                    .write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n")
+                    .await;
+                continue;
+            }
+            let result = match (params.get("code"), params.get("error")) {
+                (_, Some(error)) => Err(format!(
+                    "OAuth2 authorization failed: {} {}",
+                    error,
+                    params.get("error_description").cloned().unwrap_or_default()
+                )),
+                _ if params.get("state").map(String::as_str) != Some(state) => {
+                    Err("OAuth2 authorization failed: state mismatch".to_string())
+                }
+                (Some(code), _) => Ok(code.clone()),
+                _ => Err("OAuth2 authorization failed".to_string()),
+            };
+            let body = match &result {
+                Ok(_) => "Authorized,you can close this window and go back to weaver.".to_string(),
+                Err(err) => err.clone(),
+            };
+            let _ = stream
+                .write_all(
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    )
+                    .as_bytes(),
+                )
+                .await;
+            return result;
+        }
+    };
+    tokio::time::timeout(AUTHORIZE_TIMEOUT, accept)
+        .await
+        .unwrap_or_else(|_| Err("Timeout waiting for OAuth2 authorization".to_string()))
+}
+
+/// Serve `responses` one by one as a stub token server,return the url and bodies of received requests.
+#[cfg(test)]
+fn stub_token_server(responses: Vec<&'static str>) -> (String, std::sync::mpsc::Receiver<String>) {
+    use std::io::{BufRead, BufReader, Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/token", listener.local_addr().unwrap());
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        for response in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut content_length = 0;
+            let mut head = String::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.to_lowercase().starts_with("content-length:") {
+                    content_length = line[15..].trim().parse().unwrap();
+                }
+                head.push_str(&line);
+                if line == "\r\n" {
+                    break;
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            sender
+                .send(head + &String::from_utf8(body).unwrap())
+                .unwrap();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                response.len(),
+                response
+            )
+            .unwrap();
+        }
+    });
+    (url, receiver)
+}
+
+#[test]
+fn test_client_credentials() {
+    let (token_url, requests) = stub_token_server(vec![
+        r#"{"access_token":"first","token_type":"Bearer","expires_in":10,"refresh_token":"refresh"}"#,
+        r#"{"access_token":"second","token_type":"Bearer","expires_in":3600}"#,
+    ]);
+    let oauth2 = OAuth2 {
+        token_url,
+        client_id: "weaver".to_string(),
+        client_secret: "secret".to_string(),
+        scope: "read".to_string(),
+        ..Default::default()
+    };
+    let client = TokenClient {
+        client: reqwest::Client::new(),
+        timeout: None,
+    };
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let access_token = || runtime.block_on(oauth2.access_token(&client));
+
+    assert_eq!(access_token().unwrap(), "first");
+    let request = requests.recv().unwrap();
+    assert!(request.starts_with("POST /token"));
+    assert!(request.contains("grant_type=client_credentials"));
+    assert!(request.contains("scope=read"));
+    // `weaver:secret`
+    assert!(request.contains("Basic d2VhdmVyOnNlY3JldA=="));
+
+    // Expires in 10 seconds,which is less than `EXPIRY_MARGIN`,so it's refreshed.
+    assert_eq!(access_token().unwrap(), "second");
+    let request = requests.recv().unwrap();
+    assert!(request.contains("grant_type=refresh_token"));
+    assert!(request.contains("refresh_token=refresh"));
+
+    // Cached
+    assert_eq!(access_token().unwrap(), "second");
+    // Token isn't reused after secret is changed.
+    let changed = OAuth2 {
+        client_secret: "changed".to_string(),
+        ..oauth2.clone()
+    };
+    assert!(changed.cached_token().is_none());
+    oauth2.clear_token();
+    assert!(oauth2.cached_token().is_none());
+}
+
+#[test]
+fn test_authorization_code() {
+    let (token_url, requests) = stub_token_server(vec![
+        r#"{"access_token":"code_token","token_type":"Bearer"}"#,
+    ]);
+    let oauth2 = OAuth2 {
+        grant: GrantType::AuthorizationCode,
+        token_url,
+        auth_url: "http://127.0.0.1/authorize".to_string(),
+        client_id: "weaver".to_string(),
+        client_authentication: ClientAuthentication::Body,
+        ..Default::default()
+    };
+    let client = TokenClient {
+        client: reqwest::Client::new(),
+        timeout: None,
+    };
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+
+    let mut challenge = String::new();
+    let token = runtime.block_on(oauth2.access_token_with(&client, |url| {
+        // Act as browser which is redirected back by authorization server.
+        let url = Url::parse(url).unwrap();
+        let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+        challenge = params["code_challenge"].clone();
+        let redirect = format!(
+            "{}?code=the_code&state={}",
+            params["redirect_uri"], params["state"]
+        );
+        std::thread::spawn(move || {
+            reqwest::blocking::Client::new()
+                .get(redirect)
+                .send()
+                .unwrap()
+                .text()
+                .unwrap()
+        });
+        true
+    }));
+    assert_eq!(token.unwrap(), "code_token");
+
+    let request = requests.recv().unwrap();
+    assert!(request.contains("grant_type=authorization_code"));
+    assert!(request.contains("code=the_code"));
+    assert!(request.contains("client_id=weaver"));
+    let verifier = request
+        .split('&')
+        .find_map(|param| param.strip_prefix("code_verifier="))
+        .unwrap();
+    assert_eq!(
+        URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes())),
+        challenge
+    );
+}
+
+#[test]
+fn test_authorization_canceled() {
+    let oauth2 = OAuth2 {
+        grant: GrantType::AuthorizationCode,
+        token_url: "http://127.0.0.1:1/token".to_string(),
+        auth_url: "http://127.0.0.1/authorize".to_string(),
+        client_id: "canceled".to_string(),
+        ..Default::default()
+    };
+    let client = TokenClient {
+        client: reqwest::Client::new(),
+        timeout: None,
+    };
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+
+    let mut redirect_uri = String::new();
+    let waiting = runtime.block_on(async {
+        let fetch = oauth2.access_token_with(&client, |url| {
+            let url = Url::parse(url).unwrap();
+            redirect_uri = url
+                .query_pairs()
+                .find(|(key, _)| key == "redirect_uri")
+                .unwrap()
+                .1
+                .to_string();
+            // Browser isn't opened.
+            false
+        });
+        tokio::pin!(fetch);
+        let waiting = tokio::time::timeout(Duration::from_millis(200), &mut fetch).await;
+        // The url is shown while waiting.
+        let authorizing = oauth2.authorizing().unwrap();
+        assert!(!authorizing.opened);
+        assert!(authorizing.url.starts_with("http://127.0.0.1/authorize?"));
+        waiting.is_err()
+    });
+    assert!(waiting);
+
+    // Dropping the flow closes redirect listener.
+    assert!(oauth2.authorizing().is_none());
+    let addr = Url::parse(&redirect_uri)
+        .unwrap()
+        .socket_addrs(|| None)
+        .unwrap()[0];
+    assert!(std::net::TcpStream::connect(addr).is_err());
+}
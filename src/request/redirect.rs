@@ -0,0 +1,188 @@
+use std::time::Duration;
+
+use eframe::egui::{CollapsingHeader, DragValue, Grid, Ui};
+use reqwest::{Method, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+
+use crate::color;
+use crate::request::response::format_duration;
+
+/// How redirects of a request are followed.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Redirect {
+    pub follow: bool,
+    /// Redirects more than this aren't followed,the last one is shown as response.
+    pub max: u32,
+    /// Keep method and body on `307` and `308` as standard,otherwise they are changed to `GET` like `303`.
+    pub keep_method: bool,
+}
+
+/// A redirect response which has been followed.
+#[derive(Clone)]
+pub struct Hop {
+    pub method: String,
+    pub url: String,
+    pub status: StatusCode,
+    pub location: String,
+    pub headers: Vec<(String, String)>,
+    /// Time from sending request to receiving this response.
+    pub elapsed: Duration,
+}
+
+/// Next request of a redirect.
+pub struct Follow {
+    pub method: Method,
+    /// Body of the original request is sent again.
+    pub body: bool,
+    /// Credentials are only sent to the original host.
+    pub credentials: bool,
+}
+
+impl Default for Redirect {
+    fn default() -> Self {
+        Self {
+            follow: true,
+            max: 10,
+            keep_method: true,
+        }
+    }
+}
+
+impl Redirect {
+    /// Request to send for a redirect response,`None` if it isn't followed.
+    pub fn follow(
+        &self,
+        method: &Method,
+        status: StatusCode,
+        from: &Url,
+        to: &Url,
+        hops: usize,
+    ) -> Option<Follow> {
+        if !self.follow || hops >= self.max as usize {
+            return None;
+        }
+        let keep = match status {
+            StatusCode::TEMPORARY_REDIRECT | StatusCode::PERMANENT_REDIRECT => self.keep_method,
+            // Browsers change `POST` to `GET` on `301` and `302` too.
+            StatusCode::MOVED_PERMANENTLY | StatusCode::FOUND => *method != Method::POST,
+            StatusCode::SEE_OTHER => *method == Method::HEAD,
+            _ => return None,
+        };
+        Some(Follow {
+            method: if keep { method.clone() } else { Method::GET },
+            body: keep,
+            credentials: from.host_str() == to.host_str()
+                && from.port_or_known_default() == to.port_or_known_default(),
+        })
+    }
+
+    pub fn view(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.follow, "Follow redirects");
+            ui.add_enabled_ui(self.follow, |ui| {
+                ui.label("at most");
+                ui.add(DragValue::new(&mut self.max).clamp_range(0..=100));
+            });
+        });
+        ui.add_enabled_ui(self.follow, |ui| {
+            ui.checkbox(&mut self.keep_method, "Keep method and body on 307 and 308")
+                .on_hover_text("Otherwise they are changed to GET without body");
+        });
+    }
+}
+
+/// Redirect chain in response panel,`url` is the final one.
+pub fn chain_view(ui: &mut Ui, hops: &[Hop], url: &str, status: StatusCode) {
+    for (i, hop) in hops.iter().enumerate() {
+        // Same url has been visited,it's a loop.
+        let looped = hops[..i].iter().any(|previous| previous.url == hop.url);
+        CollapsingHeader::new(format!(
+            "{} {} {} {}",
+            i + 1,
+            hop.status.as_str(),
+            hop.method,
+            hop.url
+        ))
+        .id_source(format!("redirect_hop_{}", i))
+        .show(ui, |ui| {
+            Grid::new(format!("redirect_hop_grid_{}", i))
+                .num_columns(2)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label("Location");
+                    let mut location = hop.location.as_str();
+                    ui.text_edit_singleline(&mut location);
+                    ui.end_row();
+                    ui.label("Time");
+                    ui.label(format_duration(hop.elapsed));
+                    ui.end_row();
+                    for (key, value) in &hop.headers {
+                        ui.label(key);
+                        let mut value = value.as_str();
+                        ui.text_edit_singleline(&mut value);
+                        ui.end_row();
+                    }
+                });
+        });
+        if looped {
+            ui.colored_label(color::CRIMSON, "↻ Visited before,redirects may loop");
+        }
+    }
+    ui.horizontal(|ui| {
+        ui.colored_label(color::SEA_GREEN, status.as_str());
+        ui.label(url);
+    });
+    if status.is_redirection() {
+        ui.colored_label(color::DARK_ORANGE, "Not followed");
+    }
+}
+
+#[test]
+fn test_follow() {
+    let redirect = Redirect::default();
+    let from = Url::parse("https://sso.local/login").unwrap();
+    let same = Url::parse("https://sso.local:443/callback").unwrap();
+    let other = Url::parse("https://app.local/").unwrap();
+    let follow = |redirect: &Redirect, method: Method, status: u16, to: &Url, hops: usize| {
+        redirect
+            .follow(
+                &method,
+                StatusCode::from_u16(status).unwrap(),
+                &from,
+                to,
+                hops,
+            )
+            .map(|follow| (follow.method, follow.body, follow.credentials))
+    };
+    assert_eq!(
+        follow(&redirect, Method::POST, 302, &same, 0),
+        Some((Method::GET, false, true))
+    );
+    assert_eq!(
+        follow(&redirect, Method::PUT, 301, &other, 0),
+        Some((Method::PUT, true, false))
+    );
+    assert_eq!(
+        follow(&redirect, Method::POST, 303, &same, 0),
+        Some((Method::GET, false, true))
+    );
+    assert_eq!(
+        follow(&redirect, Method::POST, 307, &same, 9),
+        Some((Method::POST, true, true))
+    );
+    assert_eq!(follow(&redirect, Method::POST, 308, &same, 10), None);
+    assert_eq!(follow(&redirect, Method::GET, 304, &same, 0), None);
+    let changing = Redirect {
+        keep_method: false,
+        ..Default::default()
+    };
+    assert_eq!(
+        follow(&changing, Method::POST, 307, &same, 0),
+        Some((Method::GET, false, true))
+    );
+    let off = Redirect {
+        follow: false,
+        ..Default::default()
+    };
+    assert_eq!(follow(&off, Method::GET, 302, &same, 0), None);
+}
@@ -0,0 +1,272 @@
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use eframe::egui::{FontSelection, Grid, Layout, ScrollArea, TextEdit, Ui};
+use reqwest::{StatusCode, Version};
+use serde_json::Value;
+
+use crate::color;
+use crate::request::body::{self, BodyKind, Image};
+use crate::request::json;
+use crate::request::redirect::{self, Hop};
+use crate::request::sse::EventStream;
+use crate::request::tls::TlsInfo;
+
+#[derive(Clone, Default)]
+pub struct Response {
+    /// Decoded text of body,it's empty if body isn't text.
+    pub body: String,
+    pub bytes: Vec<u8>,
+    pub kind: BodyKind,
+    /// Decoded image if body is an image.
+    pub image: Option<Result<Image, String>>,
+    pub size: Option<u64>,
+    pub code: StatusCode,
+    pub headers: Vec<(String, String)>,
+    pub version: Version,
+    pub remote_addr: Option<SocketAddr>,
+    /// Proxy which request is sent through,password is hidden.
+    pub proxy: Option<String>,
+    /// TLS of the connection if it's `https`.
+    pub tls: Option<TlsInfo>,
+    /// Redirects which have been followed.
+    pub redirects: Vec<Hop>,
+    /// Url of the final response.
+    pub url: String,
+    /// Time from sending request to receiving response headers.
+    pub waiting: Duration,
+    /// Time from sending request to receiving the whole body.
+    pub elapsed: Duration,
+    /// Parsed body if it's json.
+    pub json: Option<Value>,
+    /// Body re-indented if it's json.
+    pub pretty: Option<String>,
+    /// Events read in background if it's `text/event-stream`.
+    pub events: Option<EventStream>,
+}
+
+#[derive(Eq, PartialEq, Clone, Copy, Default)]
+pub enum ResponseTab {
+    #[default]
+    Body,
+    Headers,
+    Redirects,
+    Info,
+}
+
+/// How body is shown,`Pretty` shows json highlighted or image.
+#[derive(Eq, PartialEq, Clone, Copy, Default)]
+pub enum BodyView {
+    #[default]
+    Pretty,
+    Tree,
+    Raw,
+    Hex,
+}
+
+impl Response {
+    pub fn view(&self, ui: &mut Ui, tab: &mut ResponseTab, body_view: &mut BodyView) {
+        ui.label(format!(
+            "{} {} {} ,Time: {}",
+            self.code.as_str(),
+            self.code.canonical_reason().unwrap_or(""),
+            match self.size {
+                None => {
+                    "".to_string()
+                }
+                Some(size) => {
+                    format!(" ,Size: {}", size)
+                }
+            },
+            format_duration(self.elapsed)
+        ));
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            ui.selectable_value(tab, ResponseTab::Body, "BODY");
+            ui.selectable_value(
+                tab,
+                ResponseTab::Headers,
+                format!("HEADERS ({})", self.headers.len()),
+            );
+            if !self.redirects.is_empty() {
+                ui.selectable_value(
+                    tab,
+                    ResponseTab::Redirects,
+                    format!("REDIRECTS ({})", self.redirects.len()),
+                );
+            }
+            ui.selectable_value(tab, ResponseTab::Info, "INFO");
+        });
+        ui.add_space(5.0);
+
+        ScrollArea::vertical()
+            .max_height(ui.available_height())
+            .show(ui, |ui| {
+                ui.group(|ui| {
+                    ui.set_width(ui.available_width());
+                    match tab {
+                        ResponseTab::Body => self.body_view(ui, body_view),
+                        ResponseTab::Headers => self.headers_view(ui),
+                        ResponseTab::Redirects => {
+                            redirect::chain_view(ui, &self.redirects, &self.url, self.code)
+                        }
+                        ResponseTab::Info => self.info_view(ui),
+                    }
+                })
+            });
+    }
+
+    fn body_view(&self, ui: &mut Ui, body_view: &mut BodyView) {
+        if let Some(events) = &self.events {
+            events.view(ui);
+            return;
+        }
+        ui.horizontal(|ui| {
+            match (self.kind, &self.json) {
+                (BodyKind::Text, Some(_)) => {
+                    ui.selectable_value(body_view, BodyView::Pretty, "Pretty");
+                    ui.selectable_value(body_view, BodyView::Tree, "Tree");
+                    ui.selectable_value(body_view, BodyView::Raw, "Raw");
+                }
+                (BodyKind::Text, None) => {
+                    if ui
+                        .selectable_label(*body_view != BodyView::Hex, "Raw")
+                        .clicked()
+                    {
+                        *body_view = BodyView::Raw;
+                    }
+                }
+                (BodyKind::Image, _) => {
+                    if ui
+                        .selectable_label(*body_view != BodyView::Hex, "Image")
+                        .clicked()
+                    {
+                        *body_view = BodyView::Pretty;
+                    }
+                }
+                (BodyKind::Binary, _) => {
+                    ui.colored_label(color::GRAY, format!("{} bytes", self.bytes.len()));
+                }
+            }
+            if self.kind != BodyKind::Binary {
+                ui.selectable_value(body_view, BodyView::Hex, "Hex");
+            }
+            ui.with_layout(Layout::right_to_left(), |ui| {
+                body::save_button(ui, &self.bytes, &self.headers, &self.url);
+            });
+        });
+        ui.add_space(5.0);
+        if self.kind == BodyKind::Binary || *body_view == BodyView::Hex {
+            body::hex_view(ui, &self.bytes);
+            return;
+        }
+        match &self.image {
+            Some(Ok(image)) => {
+                image.view(ui);
+                return;
+            }
+            Some(Err(err)) => {
+                ui.colored_label(color::CRIMSON, format!("Invalid image: {}", err));
+                body::hex_view(ui, &self.bytes);
+                return;
+            }
+            None => {}
+        }
+        if let (Some(value), Some(pretty)) = (&self.json, &self.pretty) {
+            match body_view {
+                BodyView::Pretty => {
+                    let mut layouter = |ui: &Ui, string: &str, wrap_width: f32| {
+                        let font_id = FontSelection::default().resolve(ui.style());
+                        ui.fonts()
+                            .layout_job(json::highlight(string, font_id, wrap_width))
+                    };
+                    let mut pretty = pretty.as_str();
+                    ui.add(
+                        TextEdit::multiline(&mut pretty)
+                            .desired_width(f32::INFINITY)
+                            .layouter(&mut layouter),
+                    );
+                    return;
+                }
+                BodyView::Tree => {
+                    json::tree_view(ui, value);
+                    return;
+                }
+                BodyView::Raw | BodyView::Hex => {}
+            }
+        }
+        ui.vertical_centered_justified(|ui| {
+            ui.add_enabled_ui(true, |ui| {
+                let mut response_body = self.body.as_str();
+                ui.text_edit_multiline(&mut response_body)
+            });
+        });
+    }
+
+    fn headers_view(&self, ui: &mut Ui) {
+        Grid::new("response_headers_grid")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                for (key, value) in &self.headers {
+                    ui.label(key);
+                    let mut value = value.as_str();
+                    ui.text_edit_singleline(&mut value);
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn info_view(&self, ui: &mut Ui) {
+        Grid::new("response_info_grid")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Status");
+                ui.label(format!(
+                    "{} {}",
+                    self.code.as_str(),
+                    self.code.canonical_reason().unwrap_or("")
+                ));
+                ui.end_row();
+                ui.label("HTTP version");
+                ui.label(format!("{:?}", self.version));
+                ui.end_row();
+                ui.label("Remote address");
+                ui.label(match self.remote_addr {
+                    None => "-".to_string(),
+                    Some(addr) => addr.to_string(),
+                });
+                ui.end_row();
+                ui.label("Proxy");
+                ui.label(self.proxy.as_deref().unwrap_or("Direct"));
+                ui.end_row();
+                if let Some(tls) = &self.tls {
+                    tls.grid_view(ui);
+                }
+                ui.label("Size");
+                ui.label(match self.size {
+                    None => format!("{} (body)", self.bytes.len()),
+                    Some(size) => size.to_string(),
+                });
+                ui.end_row();
+                ui.label("Waiting");
+                ui.label(format_duration(self.waiting));
+                ui.end_row();
+                ui.label("Download");
+                ui.label(format_duration(self.elapsed.saturating_sub(self.waiting)));
+                ui.end_row();
+                ui.label("Total");
+                ui.label(format_duration(self.elapsed));
+                ui.end_row();
+            });
+    }
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    if duration.as_millis() >= 1000 {
+        format!("{:.2} s", duration.as_secs_f64())
+    } else {
+        format!("{} ms", duration.as_millis())
+    }
+}
@@ -0,0 +1,269 @@
+use std::mem::take;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Local};
+use eframe::egui::{Context, Grid, Layout, ScrollArea, TextEdit, Ui};
+use futures_util::future::{self, Either};
+use tokio::sync::Notify;
+
+use crate::color;
+
+/// Events more than this are dropped from the oldest.
+const MAX_EVENTS: usize = 1000;
+
+/// Events of a `text/event-stream` response,which are read in background.
+/// Reading stops and the connection is closed when `stop` is called or all clones are dropped.
+#[derive(Clone)]
+pub struct EventStream {
+    shared: Arc<Shared>,
+    _guard: Arc<StopGuard>,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    stop: Notify,
+}
+
+struct State {
+    events: Vec<Event>,
+    status: Status,
+    /// Used to repaint when events arrive.
+    ctx: Option<Context>,
+}
+
+struct StopGuard(Arc<Shared>);
+
+#[derive(Clone, PartialEq, Eq)]
+enum Status {
+    Open,
+    Stopped,
+    /// Server closed the stream.
+    Ended,
+    Error(String),
+}
+
+struct Event {
+    time: DateTime<Local>,
+    /// Last event id,which is kept by following events.
+    id: Option<String>,
+    event: String,
+    data: String,
+    retry: Option<u64>,
+}
+
+/// Incremental parser of event stream,lines may be split across chunks.
+#[derive(Default)]
+struct Parser {
+    buffer: Vec<u8>,
+    /// Previous line ended with `\r`,so a following `\n` belongs to it.
+    skip_lf: bool,
+    id: Option<String>,
+    event: String,
+    data: String,
+    retry: Option<u64>,
+}
+
+impl EventStream {
+    /// Start reading events of response,it should be called inside the runtime.
+    pub fn open(response: reqwest::Response) -> Self {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                events: vec![],
+                status: Status::Open,
+                ctx: None,
+            }),
+            stop: Notify::new(),
+        });
+        tokio::spawn(read(shared.clone(), response));
+        Self {
+            _guard: Arc::new(StopGuard(shared.clone())),
+            shared,
+        }
+    }
+
+    pub fn stop(&self) {
+        self.shared.stop.notify_one();
+    }
+
+    pub fn view(&self, ui: &mut Ui) {
+        let mut state = self.shared.state.lock().unwrap();
+        if state.ctx.is_none() {
+            state.ctx = Some(ui.ctx().clone());
+        }
+        ui.horizontal(|ui| {
+            match &state.status {
+                Status::Open => ui.colored_label(color::SEA_GREEN, "Streaming…"),
+                Status::Stopped => ui.colored_label(color::GRAY, "Stopped"),
+                Status::Ended => ui.colored_label(color::GRAY, "Closed by server"),
+                Status::Error(err) => ui.colored_label(color::CRIMSON, err),
+            };
+            ui.label(format!("{} events", state.events.len()));
+            ui.with_layout(Layout::right_to_left(), |ui| {
+                if ui
+                    .add_enabled(
+                        state.status == Status::Open,
+                        eframe::egui::Button::new("Stop"),
+                    )
+                    .clicked()
+                {
+                    self.stop();
+                }
+            });
+        });
+        ui.add_space(5.0);
+        ScrollArea::vertical()
+            .id_source("event_stream")
+            .max_height(400.0)
+            .stick_to_bottom()
+            .show(ui, |ui| {
+                Grid::new("event_stream_grid")
+                    .num_columns(4)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for event in &state.events {
+                            ui.label(event.time.format("%H:%M:%S%.3f").to_string());
+                            ui.colored_label(color::DODER_BLUE, &event.event);
+                            let mut info = event.id.clone().unwrap_or_default();
+                            if let Some(retry) = event.retry {
+                                info = format!("{} retry {}ms", info, retry);
+                            }
+                            ui.colored_label(color::GRAY, info.trim());
+                            let mut data = event.data.as_str();
+                            ui.add(TextEdit::multiline(&mut data).desired_rows(1));
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+}
+
+impl Shared {
+    fn update(&self, events: Vec<Event>, status: Option<Status>) {
+        let mut state = self.state.lock().unwrap();
+        state.events.extend(events);
+        let len = state.events.len();
+        if len > MAX_EVENTS {
+            state.events.drain(..len - MAX_EVENTS);
+        }
+        if let Some(status) = status {
+            state.status = status;
+        }
+        if let Some(ctx) = &state.ctx {
+            ctx.request_repaint();
+        }
+    }
+}
+
+impl Drop for StopGuard {
+    fn drop(&mut self) {
+        self.0.stop.notify_one();
+    }
+}
+
+async fn read(shared: Arc<Shared>, mut response: reqwest::Response) {
+    let mut parser = Parser::default();
+    let status = loop {
+        let chunk = Box::pin(response.chunk());
+        let stop = Box::pin(shared.stop.notified());
+        match future::select(chunk, stop).await {
+            Either::Left((Ok(Some(chunk)), _)) => shared.update(parser.feed(&chunk), None),
+            Either::Left((Ok(None), _)) => break Status::Ended,
+            Either::Left((Err(err), _)) => break Status::Error(err.to_string()),
+            Either::Right(_) => break Status::Stopped,
+        }
+    };
+    // Dropping the unfinished body closes the connection.
+    drop(response);
+    shared.update(vec![], Some(status));
+}
+
+impl Parser {
+    fn feed(&mut self, chunk: &[u8]) -> Vec<Event> {
+        let mut events = vec![];
+        for &byte in chunk {
+            if take(&mut self.skip_lf) && byte == b'\n' {
+                continue;
+            }
+            match byte {
+                b'\r' | b'\n' => {
+                    self.skip_lf = byte == b'\r';
+                    let line = take(&mut self.buffer);
+                    events.extend(self.line(&String::from_utf8_lossy(&line)));
+                }
+                _ => self.buffer.push(byte),
+            }
+        }
+        events
+    }
+
+    fn line(&mut self, line: &str) -> Option<Event> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+        let (field, value) = match line.split_once(':') {
+            // Comment
+            Some(("", _)) => return None,
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        match field {
+            "event" => self.event = value.to_string(),
+            "data" => {
+                self.data.push_str(value);
+                self.data.push('\n');
+            }
+            "id" if !value.contains('\0') => self.id = Some(value.to_string()),
+            "retry" => self.retry = value.parse().ok().or(self.retry),
+            _ => {}
+        }
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<Event> {
+        let event = take(&mut self.event);
+        let mut data = take(&mut self.data);
+        // Event without data isn't dispatched.
+        data.pop()?;
+        Some(Event {
+            time: Local::now(),
+            id: self.id.clone(),
+            event: if event.is_empty() {
+                "message".to_string()
+            } else {
+                event
+            },
+            data,
+            retry: self.retry.take(),
+        })
+    }
+}
+
+#[test]
+fn test_parser() {
+    let mut parser = Parser::default();
+    let mut events = vec![];
+    for chunk in [
+        ": ping\n\ndata: first\n".as_bytes(),
+        b"data:second line\r",
+        b"\n\r\nevent: update\nid: 7\nretry: 3000\ndata: {\"a\"",
+        // `é` split across chunks.
+        &[b':', b' ', 0xc3],
+        &[0xa9, b'}', b'\n', b'\n'],
+        b"data\n\nid: 8\n\n",
+    ] {
+        events.extend(parser.feed(chunk));
+    }
+    let events: Vec<(&str, &str, Option<&str>, Option<u64>)> = events
+        .iter()
+        .map(|e| (e.event.as_str(), e.data.as_str(), e.id.as_deref(), e.retry))
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            ("message", "first\nsecond line", None, None),
+            ("update", "{\"a\": é}", Some("7"), Some(3000)),
+            ("message", "", Some("7"), None),
+        ]
+    );
+    assert_eq!(parser.id.as_deref(), Some("8"));
+}
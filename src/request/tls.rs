@@ -0,0 +1,275 @@
+use std::path::PathBuf;
+
+use eframe::egui::{CollapsingHeader, Grid, TextEdit, Ui};
+use serde::{Deserialize, Serialize};
+
+use crate::color;
+use crate::components;
+
+/// TLS options,those of request are added to those of settings.
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Tls {
+    /// Accept invalid certificates and host names,like `curl -k`.
+    pub insecure: bool,
+    /// PEM files of certificates which are trusted besides those of system.
+    pub ca_files: Vec<PathBuf>,
+    pub identities: Vec<ClientIdentity>,
+}
+
+/// Client certificate presented to servers which ask for it.
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct ClientIdentity {
+    /// Host which the identity is presented to,empty means all hosts.
+    pub host: String,
+    /// PKCS#12 archive(`.p12`,`.pfx`) or PEM certificate.
+    pub cert: PathBuf,
+    /// PKCS#8 PEM key,it may be in the certificate file instead.
+    pub key: Option<PathBuf>,
+    /// Password of PKCS#12 archive.
+    pub password: String,
+}
+
+/// TLS of the connection which response is received from.
+#[derive(Clone)]
+pub struct TlsInfo {
+    /// Leaf certificate of server,the rest of chain isn't exposed by the connection.
+    pub certificate: Option<CertificateInfo>,
+}
+
+#[derive(Clone)]
+pub struct CertificateInfo {
+    pub subject: String,
+    pub issuer: String,
+    pub not_before: String,
+    pub not_after: String,
+    pub serial: String,
+}
+
+impl Tls {
+    /// Options of both,`self` goes first.
+    pub fn merge(&self, other: &Tls) -> Tls {
+        let mut merged = self.clone();
+        merged.insecure |= other.insecure;
+        for ca_file in &other.ca_files {
+            if !merged.ca_files.contains(ca_file) {
+                merged.ca_files.push(ca_file.clone());
+            }
+        }
+        merged.identities.extend(other.identities.iter().cloned());
+        merged
+    }
+
+    /// The first identity for host,those without certificate are skipped.
+    pub fn identity(&self, host: &str) -> Option<&ClientIdentity> {
+        self.identities.iter().find(|identity| {
+            !identity.cert.as_os_str().is_empty()
+                && (identity.host.is_empty() || identity.host.eq_ignore_ascii_case(host))
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &Tls::default()
+    }
+
+    pub fn view(&mut self, ui: &mut Ui, id: &str) {
+        ui.checkbox(&mut self.insecure, "Accept invalid certificates")
+            .on_hover_text("Like curl -k,don't use it in production");
+        ui.add_space(5.0);
+        ui.label("CA certificates (PEM)");
+        let mut remove = None;
+        for (i, ca_file) in self.ca_files.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                let mut path = Some(ca_file.clone());
+                components::file_button(ui, &mut path);
+                if let Some(path) = path {
+                    *ca_file = path;
+                }
+                if ui.button("🗑").clicked() {
+                    remove = Some(i);
+                }
+            });
+        }
+        if let Some(i) = remove {
+            self.ca_files.remove(i);
+        }
+        if ui.button("Add CA file").clicked() {
+            if let Some(path) = rfd::FileDialog::new()
+                .add_filter("PEM", &["pem", "crt", "cer"])
+                .pick_file()
+            {
+                self.ca_files.push(path);
+            }
+        }
+        ui.add_space(5.0);
+        ui.label("Client certificates");
+        let mut remove = None;
+        Grid::new(format!("{}_identities", id))
+            .num_columns(5)
+            .show(ui, |ui| {
+                if !self.identities.is_empty() {
+                    ui.label("Host");
+                    ui.label("Certificate");
+                    ui.label("Key");
+                    ui.label("Password");
+                    ui.end_row();
+                }
+                for (i, identity) in self.identities.iter_mut().enumerate() {
+                    ui.add(
+                        TextEdit::singleline(&mut identity.host)
+                            .hint_text("All hosts")
+                            .desired_width(150.0),
+                    );
+                    let mut cert = Some(identity.cert.clone());
+                    components::file_button(ui, &mut cert);
+                    identity.cert = cert.unwrap_or_default();
+                    components::file_button(ui, &mut identity.key)
+                        .on_hover_text("Not needed if it's in certificate file");
+                    ui.add(
+                        TextEdit::singleline(&mut identity.password)
+                            .password(true)
+                            .hint_text("PKCS#12")
+                            .desired_width(100.0),
+                    );
+                    if ui.button("🗑").clicked() {
+                        remove = Some(i);
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(i) = remove {
+            self.identities.remove(i);
+        }
+        if ui.button("Add client certificate").clicked() {
+            self.identities.push(Default::default());
+        }
+    }
+}
+
+impl ClientIdentity {
+    pub fn load(&self) -> Result<reqwest::Identity, String> {
+        let read = |path: &PathBuf| {
+            std::fs::read(path).map_err(|err| format!("{}: {}", path.display(), err))
+        };
+        let cert = read(&self.cert)?;
+        let is_pkcs12 = self.cert.extension().is_some_and(|extension| {
+            extension.eq_ignore_ascii_case("p12") || extension.eq_ignore_ascii_case("pfx")
+        });
+        let identity = match (&self.key, is_pkcs12) {
+            (None, true) => reqwest::Identity::from_pkcs12_der(&cert, &self.password),
+            (Some(key), _) => reqwest::Identity::from_pkcs8_pem(&cert, &read(key)?),
+            (None, false) => reqwest::Identity::from_pkcs8_pem(&cert, &cert),
+        };
+        identity.map_err(|err| {
+            format!(
+                "Invalid client certificate {}: {}",
+                self.cert.display(),
+                err
+            )
+        })
+    }
+}
+
+impl TlsInfo {
+    /// Info of the real connection,client must be built with `tls_info(true)`.
+    pub fn of(response: &reqwest::Response) -> Option<Self> {
+        let info = response.extensions().get::<reqwest::tls::TlsInfo>()?;
+        Some(Self {
+            certificate: info.peer_certificate().map(CertificateInfo::parse),
+        })
+    }
+}
+
+impl CertificateInfo {
+    fn parse(der: &[u8]) -> Self {
+        match x509_parser::parse_x509_certificate(der) {
+            Ok((_, certificate)) => {
+                let validity = certificate.validity();
+                Self {
+                    subject: certificate.subject().to_string(),
+                    issuer: certificate.issuer().to_string(),
+                    not_before: validity.not_before.to_string(),
+                    not_after: validity.not_after.to_string(),
+                    serial: certificate.raw_serial_as_string(),
+                }
+            }
+            Err(err) => Self {
+                subject: format!("Invalid certificate: {}", err),
+                issuer: "".to_string(),
+                not_before: "".to_string(),
+                not_after: "".to_string(),
+                serial: "".to_string(),
+            },
+        }
+    }
+}
+
+impl TlsInfo {
+    /// Rows of grid with two columns.
+    pub fn grid_view(&self, ui: &mut Ui) {
+        ui.label("Certificate");
+        match &self.certificate {
+            None => {
+                ui.colored_label(color::GRAY, "Not available");
+            }
+            Some(certificate) => {
+                CollapsingHeader::new(&certificate.subject)
+                    .id_source("tls_certificate")
+                    .show(ui, |ui| {
+                        Grid::new("tls_certificate_grid")
+                            .num_columns(2)
+                            .show(ui, |ui| {
+                                ui.label("Issuer");
+                                ui.label(&certificate.issuer);
+                                ui.end_row();
+                                ui.label("Not before");
+                                ui.label(&certificate.not_before);
+                                ui.end_row();
+                                ui.label("Not after");
+                                ui.label(&certificate.not_after);
+                                ui.end_row();
+                                ui.label("Serial");
+                                ui.label(&certificate.serial);
+                                ui.end_row();
+                            });
+                    });
+            }
+        }
+        ui.end_row();
+    }
+}
+
+#[test]
+fn test_merge() {
+    let identity = |host: &str| ClientIdentity {
+        host: host.to_string(),
+        cert: PathBuf::from(format!("{}.p12", host)),
+        ..Default::default()
+    };
+    let settings = Tls {
+        insecure: false,
+        ca_files: vec![PathBuf::from("dev-ca.pem")],
+        identities: vec![identity("")],
+    };
+    let request = Tls {
+        insecure: true,
+        ca_files: vec![PathBuf::from("dev-ca.pem"), PathBuf::from("other.pem")],
+        identities: vec![identity("api.local")],
+    };
+    let merged = request.merge(&settings);
+    assert!(merged.insecure);
+    assert_eq!(merged.ca_files.len(), 2);
+    assert_eq!(
+        merged.identity("API.local").unwrap().cert,
+        PathBuf::from("api.local.p12")
+    );
+    assert_eq!(
+        merged.identity("example.com").unwrap().cert,
+        PathBuf::from(".p12")
+    );
+    assert!(Tls::default().identity("example.com").is_none());
+    let unpicked = Tls {
+        identities: vec![Default::default()],
+        ..Default::default()
+    };
+    assert!(unpicked.identity("example.com").is_none());
+}
@@ -0,0 +1,737 @@
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::ops::Add;
+use std::sync::mpsc::{channel, Receiver, Sender, TryRecvError};
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+use eframe::egui::{
+    Button, CollapsingHeader, Context, Grid, Id, Layout, Rect, ScrollArea, TextEdit, Ui, Vec2,
+    WidgetText,
+};
+use serde::{Deserialize, Serialize};
+use tungstenite::client::IntoClientRequest;
+use tungstenite::http::HeaderValue;
+use tungstenite::protocol::frame::coding::CloseCode;
+use tungstenite::protocol::CloseFrame;
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::Message;
+
+use crate::environment::{self, Variables};
+use crate::request::http::get_uuid;
+use crate::request::{Request, RequestContext};
+use crate::{color, components};
+
+/// How long reading blocks before checking messages to send.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Entries more than this are dropped from the oldest.
+const MAX_LOG: usize = 1000;
+
+#[derive(Deserialize, Serialize)]
+pub struct WebSocket {
+    id: String,
+    name: String,
+    url: String,
+    /// Headers of handshake request.
+    header: Vec<(String, String)>,
+    /// Separated by `,`,sent as `Sec-WebSocket-Protocol`.
+    protocols: String,
+    message: String,
+    message_type: MessageType,
+    #[serde(skip)]
+    connection: Option<Connection>,
+    #[serde(skip)]
+    log: Vec<LogEntry>,
+}
+
+#[derive(Deserialize, Serialize, Eq, PartialEq, Clone, Copy, Default)]
+enum MessageType {
+    #[default]
+    Text,
+    /// Input as hex such as `01 ff`.
+    Binary,
+}
+
+struct Connection {
+    sender: Sender<Command>,
+    receiver: Receiver<Event>,
+    connected: bool,
+}
+
+enum Command {
+    Send(Message),
+    Close,
+}
+
+#[derive(Debug)]
+enum Event {
+    Connected {
+        status: u16,
+        protocol: Option<String>,
+    },
+    Received(Message),
+    Sent(Message),
+    Closed,
+    Error(String),
+}
+
+#[derive(Eq, PartialEq, Clone, Copy)]
+enum Direction {
+    In,
+    Out,
+    Info,
+    Error,
+}
+
+struct LogEntry {
+    time: DateTime<Local>,
+    direction: Direction,
+    kind: &'static str,
+    text: String,
+}
+
+impl Default for WebSocket {
+    fn default() -> Self {
+        Self {
+            id: get_uuid(),
+            name: "New websocket".to_string(),
+            url: "".to_string(),
+            header: vec![],
+            protocols: "".to_string(),
+            message: "".to_string(),
+            message_type: Default::default(),
+            connection: None,
+            log: vec![],
+        }
+    }
+}
+
+impl Request for WebSocket {
+    fn request_name(&self) -> &str {
+        if self.name.is_empty() {
+            return "WebSocket";
+        }
+        self.name.as_str()
+    }
+
+    fn badge(&self) -> Option<&str> {
+        Some("WS")
+    }
+
+    fn view(&mut self, ui: &mut Ui, context: &mut RequestContext) {
+        self.poll();
+        let variables = context.variables;
+        let (_, row_height) = crate::style::get_row_height(ui);
+        ui.add_space(10.0);
+        ui.add(
+            TextEdit::singleline(&mut self.name)
+                .margin(Vec2::new(5.0, 5.0))
+                .desired_width(f32::INFINITY),
+        );
+        ui.add_space(10.0);
+        ui.horizontal(|ui| {
+            ui.add_enabled_ui(self.connection.is_none(), |ui| {
+                let mut layouter = |ui: &Ui, string: &str, wrap_width: f32| {
+                    let font_id = eframe::egui::FontSelection::default().resolve(ui.style());
+                    ui.fonts().layout_job(environment::highlight(
+                        string, variables, font_id, wrap_width,
+                    ))
+                };
+                ui.add(
+                    TextEdit::singleline(&mut self.url)
+                        .hint_text("ws://localhost:8080/socket")
+                        .desired_width(ui.available_width() - 110.0)
+                        .layouter(&mut layouter),
+                );
+            });
+            let (text, fill) = match &self.connection {
+                None => ("Connect", color::DODER_BLUE),
+                Some(connection) if connection.connected => ("Disconnect", color::CRIMSON),
+                Some(_) => ("Cancel", color::CRIMSON),
+            };
+            let button = components::widget_with_size(
+                ui,
+                Vec2::new(100.0, row_height + 4.0),
+                Button::new(WidgetText::from(text).color(color::WHITE)).fill(fill),
+            );
+            if button.clicked() {
+                match &self.connection {
+                    None => self.connect(ui.ctx().clone(), context),
+                    Some(connection) if connection.connected => {
+                        let _ = connection.sender.send(Command::Close);
+                    }
+                    // Dropping the sender stops connecting thread.
+                    Some(_) => {
+                        self.connection = None;
+                        self.push_log(Direction::Info, "Close", "Canceled".to_string());
+                    }
+                }
+            }
+        });
+        ui.colored_label(
+            color::GRAY,
+            "Connect timeout of settings applies,proxy and TLS options don't",
+        );
+        ui.add_space(10.0);
+        CollapsingHeader::new("Handshake")
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.add_enabled_ui(self.connection.is_none(), |ui| {
+                    self.handshake_view(ui, variables, row_height);
+                });
+            });
+        ui.add_space(10.0);
+        CollapsingHeader::new("Message")
+            .default_open(true)
+            .show(ui, |ui| self.message_view(ui));
+        ui.add_space(10.0);
+        CollapsingHeader::new("Log")
+            .default_open(true)
+            .show(ui, |ui| self.log_view(ui));
+    }
+
+    fn get_id(&self) -> String {
+        self.id.to_string()
+    }
+}
+
+impl WebSocket {
+    fn handshake_view(&mut self, ui: &mut Ui, variables: &Variables, row_height: f32) {
+        Grid::new("websocket_protocol_grid")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Subprotocols");
+                ui.add(
+                    TextEdit::singleline(&mut self.protocols)
+                        .hint_text("graphql-ws, chat")
+                        .desired_width(300.0),
+                );
+                ui.end_row();
+            });
+        ui.add_space(5.0);
+        ui.label("Headers");
+        let col_width = (ui.available_width() - row_height * 2.0) / 2.0;
+        let mut label = 0;
+        self.header.retain_mut(|(key, value)| {
+            label += 1;
+            !ui.horizontal(|ui| {
+                environment::variable_text_edit(ui, key, variables, col_width);
+                environment::variable_text_edit(ui, value, variables, col_width);
+                let next_pos = ui.next_widget_position();
+                let clear_btn_rect =
+                    Rect::from_min_max(next_pos, next_pos.add(Vec2::splat(row_height / 1.5)));
+                ui.add_space(row_height);
+                components::close_button(
+                    ui,
+                    clear_btn_rect,
+                    Id::new(label.to_string() + "remove_websocket_header_btn"),
+                )
+            })
+            .inner
+            .clicked()
+        });
+        ui.add_space(5.0);
+        ui.vertical_centered(|ui| {
+            ui.style_mut().visuals.widgets.hovered.expansion = 2.0;
+            let next_pos = ui.next_widget_position();
+            let add_btn_rect =
+                Rect::from_min_max(next_pos, next_pos.add(Vec2::splat(row_height / 1.5)));
+            if components::add_button(ui, add_btn_rect, Id::new("add_websocket_header_button"))
+                .clicked()
+            {
+                self.header.push(("".to_string(), "".to_string()));
+            }
+        });
+    }
+
+    fn message_view(&mut self, ui: &mut Ui) {
+        let connected = matches!(&self.connection, Some(connection) if connection.connected);
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.message_type, MessageType::Text, "Text");
+            ui.selectable_value(&mut self.message_type, MessageType::Binary, "Binary (hex)");
+            ui.with_layout(Layout::right_to_left(), |ui| {
+                ui.add_enabled_ui(connected, |ui| {
+                    if ui.button("Ping").clicked() {
+                        self.send(Message::Ping(vec![]));
+                    }
+                    if ui.button("Send").clicked() {
+                        match self.message_type {
+                            MessageType::Text => self.send(Message::Text(self.message.clone())),
+                            MessageType::Binary => match parse_hex(&self.message) {
+                                Ok(data) => self.send(Message::Binary(data)),
+                                Err(err) => self.push_log(Direction::Error, "Error", err),
+                            },
+                        }
+                    }
+                });
+            });
+        });
+        ui.add_space(5.0);
+        ui.add(
+            TextEdit::multiline(&mut self.message)
+                .desired_rows(4)
+                .desired_width(f32::INFINITY),
+        );
+    }
+
+    fn log_view(&mut self, ui: &mut Ui) {
+        if ui.button("Clear").clicked() {
+            self.log.clear();
+        }
+        ui.add_space(5.0);
+        ScrollArea::vertical()
+            .max_height(400.0)
+            .stick_to_bottom()
+            .show(ui, |ui| {
+                Grid::new("websocket_log_grid")
+                    .num_columns(4)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for entry in &self.log {
+                            let (direction, color) = match entry.direction {
+                                Direction::In => ("IN", color::SEA_GREEN),
+                                Direction::Out => ("OUT", color::DODER_BLUE),
+                                Direction::Info => ("", color::GRAY),
+                                Direction::Error => ("", color::CRIMSON),
+                            };
+                            ui.label(entry.time.format("%H:%M:%S%.3f").to_string());
+                            ui.colored_label(color, direction);
+                            ui.colored_label(color, entry.kind);
+                            let mut text = entry.text.as_str();
+                            ui.add(TextEdit::singleline(&mut text).desired_width(f32::INFINITY));
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+
+    fn connect(&mut self, ctx: Context, context: &RequestContext) {
+        let variables = context.variables;
+        let url = environment::substitute(&self.url, variables);
+        let header = context
+            .defaults
+            .merge_header(&self.header)
+            .iter()
+            .map(|(k, v)| {
+                (
+                    environment::substitute(k, variables),
+                    environment::substitute(v, variables),
+                )
+            })
+            .collect();
+        self.push_log(Direction::Info, "Connect", url.clone());
+        self.connection = Some(Connection::open(
+            url,
+            header,
+            self.protocols.clone(),
+            context.settings.timeouts.connect(),
+            Some(ctx),
+        ));
+    }
+
+    fn send(&mut self, message: Message) {
+        if let Some(connection) = &self.connection {
+            let _ = connection.sender.send(Command::Send(message));
+        }
+    }
+
+    /// Receive events from connection thread.
+    fn poll(&mut self) {
+        let events: Vec<Event> = match &mut self.connection {
+            None => return,
+            Some(connection) => connection.receiver.try_iter().collect(),
+        };
+        for event in events {
+            match event {
+                Event::Connected { status, protocol } => {
+                    if let Some(connection) = &mut self.connection {
+                        connection.connected = true;
+                    }
+                    let text = match protocol {
+                        None => format!("Connected,status {}", status),
+                        Some(protocol) => {
+                            format!("Connected,status {},protocol {}", status, protocol)
+                        }
+                    };
+                    self.push_log(Direction::Info, "Open", text);
+                }
+                Event::Received(message) => {
+                    let (kind, text) = describe(&message);
+                    self.push_log(Direction::In, kind, text);
+                }
+                Event::Sent(message) => {
+                    let (kind, text) = describe(&message);
+                    self.push_log(Direction::Out, kind, text);
+                }
+                Event::Closed => {
+                    self.connection = None;
+                    self.push_log(Direction::Info, "Close", "Connection closed".to_string());
+                }
+                Event::Error(err) => {
+                    self.connection = None;
+                    self.push_log(Direction::Error, "Error", err);
+                }
+            }
+        }
+    }
+
+    fn push_log(&mut self, direction: Direction, kind: &'static str, text: String) {
+        self.log.push(LogEntry {
+            time: Local::now(),
+            direction,
+            kind,
+            text,
+        });
+        if self.log.len() > MAX_LOG {
+            self.log.drain(..self.log.len() - MAX_LOG);
+        }
+    }
+}
+
+impl Connection {
+    /// Connect in another thread,`ctx` is used to repaint when events arrive.
+    /// `timeout` is applied to both connecting and handshake.
+    fn open(
+        url: String,
+        header: Vec<(String, String)>,
+        protocols: String,
+        timeout: Option<Duration>,
+        ctx: Option<Context>,
+    ) -> Self {
+        let (sender, commands) = channel();
+        let (events, receiver) = channel();
+        std::thread::spawn(move || {
+            let notify = |event: Event| {
+                let _ = events.send(event);
+                if let Some(ctx) = &ctx {
+                    ctx.request_repaint();
+                }
+            };
+            if let Err(err) = run(&url, &header, &protocols, timeout, commands, &notify) {
+                notify(Event::Error(err));
+            }
+        });
+        Self {
+            sender,
+            receiver,
+            connected: false,
+        }
+    }
+}
+
+/// Socket whose `WouldBlock` is reported as `TimedOut` in handshake,
+/// otherwise TLS handshake of `tungstenite` panics when read timeout is reached.
+struct Stream {
+    socket: TcpStream,
+    handshaking: bool,
+}
+
+impl Stream {
+    fn map_err(&self, err: std::io::Error) -> std::io::Error {
+        match err.kind() {
+            ErrorKind::WouldBlock if self.handshaking => {
+                std::io::Error::new(ErrorKind::TimedOut, "Handshake timed out")
+            }
+            _ => err,
+        }
+    }
+}
+
+impl Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.socket.read(buf).map_err(|err| self.map_err(err))
+    }
+}
+
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.socket.write(buf).map_err(|err| self.map_err(err))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.socket.flush().map_err(|err| self.map_err(err))
+    }
+}
+
+/// Connect to host of request,each address is tried in turn.
+fn connect_socket(
+    uri: &tungstenite::http::Uri,
+    timeout: Option<Duration>,
+) -> Result<TcpStream, String> {
+    let host = uri.host().ok_or("No host")?;
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let port = uri.port_u16().unwrap_or(match uri.scheme_str() {
+        Some("wss") => 443,
+        _ => 80,
+    });
+    let mut last_err = "No address".to_string();
+    for addr in (host, port)
+        .to_socket_addrs()
+        .map_err(|err| err.to_string())?
+    {
+        let connected = match timeout {
+            None => TcpStream::connect(addr),
+            Some(timeout) => TcpStream::connect_timeout(&addr, timeout),
+        };
+        match connected {
+            Ok(socket) => return Ok(socket),
+            Err(err) => last_err = format!("{}: {}", addr, err),
+        }
+    }
+    Err(last_err)
+}
+
+fn run(
+    url: &str,
+    header: &[(String, String)],
+    protocols: &str,
+    timeout: Option<Duration>,
+    commands: Receiver<Command>,
+    notify: &dyn Fn(Event),
+) -> Result<(), String> {
+    let mut request = url.into_client_request().map_err(|err| err.to_string())?;
+    for (key, value) in header {
+        let name = tungstenite::http::header::HeaderName::from_bytes(key.as_bytes())
+            .map_err(|err| format!("Invalid header {}: {}", key, err))?;
+        let value = HeaderValue::from_str(value)
+            .map_err(|err| format!("Invalid header {}: {}", key, err))?;
+        request.headers_mut().append(name, value);
+    }
+    if !protocols.trim().is_empty() {
+        let value = HeaderValue::from_str(protocols.trim()).map_err(|err| err.to_string())?;
+        request
+            .headers_mut()
+            .insert("Sec-WebSocket-Protocol", value);
+    }
+    let socket = connect_socket(request.uri(), timeout)?;
+    // Handshake doesn't block longer than connecting.
+    socket
+        .set_read_timeout(timeout)
+        .and_then(|_| socket.set_write_timeout(timeout))
+        .map_err(|err| err.to_string())?;
+    let stream = Stream {
+        socket,
+        handshaking: true,
+    };
+    let (mut socket, response) =
+        tungstenite::client_tls(request, stream).map_err(|err| err.to_string())?;
+    notify(Event::Connected {
+        status: response.status().as_u16(),
+        protocol: response
+            .headers()
+            .get("Sec-WebSocket-Protocol")
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string()),
+    });
+    let stream: &mut Stream = match socket.get_mut() {
+        MaybeTlsStream::Plain(stream) => stream,
+        MaybeTlsStream::NativeTls(stream) => stream.get_mut(),
+        _ => return Err("Unsupported stream".to_string()),
+    };
+    stream.handshaking = false;
+    stream
+        .socket
+        .set_read_timeout(Some(POLL_INTERVAL))
+        .and_then(|_| stream.socket.set_write_timeout(None))
+        .map_err(|err| err.to_string())?;
+
+    loop {
+        loop {
+            match commands.try_recv() {
+                Ok(Command::Send(message)) => {
+                    socket
+                        .send(message.clone())
+                        .map_err(|err| err.to_string())?;
+                    notify(Event::Sent(message));
+                }
+                // Window dropped the connection,such as the request is deleted.
+                Ok(Command::Close) | Err(TryRecvError::Disconnected) => {
+                    socket.close(None).map_err(|err| err.to_string())?;
+                    break;
+                }
+                Err(TryRecvError::Empty) => break,
+            }
+        }
+        match socket.read() {
+            Ok(Message::Close(frame)) => {
+                notify(Event::Received(Message::Close(frame.clone())));
+                // Reply of close is sent by `tungstenite`,read until connection is closed.
+                let _ = socket.flush();
+            }
+            Ok(message) => notify(Event::Received(message)),
+            Err(tungstenite::Error::ConnectionClosed) | Err(tungstenite::Error::AlreadyClosed) => {
+                notify(Event::Closed);
+                return Ok(());
+            }
+            Err(tungstenite::Error::Io(err))
+                if err.kind() == std::io::ErrorKind::WouldBlock
+                    || err.kind() == std::io::ErrorKind::TimedOut =>
+            {
+                // Flush pong which is queued by `tungstenite`.
+                match socket.flush() {
+                    Ok(()) => {}
+                    Err(tungstenite::Error::Io(err))
+                        if err.kind() == std::io::ErrorKind::WouldBlock
+                            || err.kind() == std::io::ErrorKind::TimedOut => {}
+                    Err(err) => return Err(err.to_string()),
+                }
+            }
+            Err(err) => return Err(err.to_string()),
+        }
+    }
+}
+
+fn describe(message: &Message) -> (&'static str, String) {
+    match message {
+        Message::Text(text) => ("Text", text.clone()),
+        Message::Binary(data) => (
+            "Binary",
+            format!("{} bytes: {}", data.len(), hex::encode(data)),
+        ),
+        Message::Ping(data) => ("Ping", hex::encode(data)),
+        Message::Pong(data) => ("Pong", hex::encode(data)),
+        Message::Close(None) => ("Close", "".to_string()),
+        Message::Close(Some(CloseFrame { code, reason })) => {
+            ("Close", close_text(u16::from(*code), reason))
+        }
+        Message::Frame(_) => ("Frame", "".to_string()),
+    }
+}
+
+fn close_text(code: u16, reason: &str) -> String {
+    let name = match CloseCode::from(code) {
+        CloseCode::Normal => "Normal",
+        CloseCode::Away => "Going away",
+        CloseCode::Protocol => "Protocol error",
+        CloseCode::Unsupported => "Unsupported data",
+        CloseCode::Status => "No status",
+        CloseCode::Abnormal => "Abnormal",
+        CloseCode::Invalid => "Invalid data",
+        CloseCode::Policy => "Policy violation",
+        CloseCode::Size => "Message too big",
+        CloseCode::Extension => "Extension required",
+        CloseCode::Error => "Internal error",
+        CloseCode::Restart => "Service restart",
+        CloseCode::Again => "Try again later",
+        _ => "",
+    };
+    format!("{} {} {}", code, name, reason).trim().to_string()
+}
+
+/// Parse hex such as `01 ff 0A`,whitespaces are ignored.
+fn parse_hex(text: &str) -> Result<Vec<u8>, String> {
+    let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    hex::decode(text).map_err(|err| format!("Invalid hex: {}", err))
+}
+
+#[test]
+fn test_websocket() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}/echo", listener.local_addr().unwrap());
+    // Echo server which closes connection after receiving `bye`.
+    std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        #[allow(clippy::result_large_err)]
+        let callback =
+            |request: &tungstenite::handshake::server::Request,
+             mut response: tungstenite::handshake::server::Response| {
+                assert_eq!(request.headers()["X-Token"], "abc");
+                response
+                    .headers_mut()
+                    .insert("Sec-WebSocket-Protocol", HeaderValue::from_static("chat"));
+                Ok(response)
+            };
+        let mut socket = tungstenite::accept_hdr(stream, callback).unwrap();
+        loop {
+            match socket.read().unwrap() {
+                Message::Text(text) if text == "bye" => {
+                    socket
+                        .close(Some(CloseFrame {
+                            code: CloseCode::Away,
+                            reason: "see you".into(),
+                        }))
+                        .unwrap();
+                }
+                message @ (Message::Text(_) | Message::Binary(_)) => socket.send(message).unwrap(),
+                Message::Close(_) => break,
+                _ => {}
+            }
+        }
+        // Flush reply of close.
+        let _ = socket.flush();
+    });
+
+    let connection = Connection::open(
+        url,
+        vec![("X-Token".to_string(), "abc".to_string())],
+        "chat".to_string(),
+        Some(Duration::from_secs(5)),
+        None,
+    );
+    let next = || {
+        connection
+            .receiver
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap()
+    };
+    assert!(matches!(
+        next(),
+        Event::Connected { status: 101, protocol: Some(protocol) } if protocol == "chat"
+    ));
+
+    connection
+        .sender
+        .send(Command::Send(Message::Text("hello".to_string())))
+        .unwrap();
+    assert!(matches!(next(), Event::Sent(Message::Text(text)) if text == "hello"));
+    assert!(matches!(next(), Event::Received(Message::Text(text)) if text == "hello"));
+
+    let data = parse_hex("01 ff").unwrap();
+    connection
+        .sender
+        .send(Command::Send(Message::Binary(data.clone())))
+        .unwrap();
+    assert!(matches!(next(), Event::Sent(Message::Binary(_))));
+    assert!(matches!(next(), Event::Received(Message::Binary(received)) if received == data));
+
+    connection
+        .sender
+        .send(Command::Send(Message::Text("bye".to_string())))
+        .unwrap();
+    assert!(matches!(next(), Event::Sent(_)));
+    match next() {
+        Event::Received(message) => {
+            assert_eq!(
+                describe(&message),
+                ("Close", "1001 Going away see you".to_string())
+            )
+        }
+        event => panic!("{:?}", event),
+    }
+    assert!(matches!(next(), Event::Closed));
+}
+
+#[test]
+fn test_handshake_timeout() {
+    // Server which accepts connection but never answers handshake.
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}/", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        let _streams: Vec<TcpStream> = listener.incoming().map(Result::unwrap).collect();
+    });
+    // TLS handshake times out too.
+    for url in [url.clone(), url.replacen("ws", "wss", 1)] {
+        let connection = Connection::open(
+            url,
+            vec![],
+            "".to_string(),
+            Some(Duration::from_millis(300)),
+            None,
+        );
+        assert!(matches!(
+            connection.receiver.recv_timeout(Duration::from_secs(5)),
+            Ok(Event::Error(_))
+        ));
+    }
+}
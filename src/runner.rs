@@ -0,0 +1,674 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use eframe::egui::{
+    Button, Checkbox, ComboBox, Context, DragValue, Grid, ProgressBar, ScrollArea, Ui, Window,
+};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::collection::Collections;
+use crate::color;
+use crate::environment::Variables;
+use crate::request::assertion::{self, Assertion, Outcome};
+use crate::request::extraction::{self, Extraction};
+use crate::request::http::Sending;
+use crate::request::response::format_duration;
+use crate::request::{Request, RequestContext};
+
+/// Run selected http requests one by one,results are shown in runner window.
+#[derive(Deserialize, Serialize)]
+pub struct Runner {
+    /// Ids of selected requests,they are run in order of tree.
+    selected: Vec<String>,
+    iterations: u32,
+    /// Milliseconds to wait between requests.
+    delay: u64,
+    stop_on_failure: bool,
+    /// CSV or JSON file whose rows bind variables,requests are run once per row.
+    #[serde(default)]
+    data_file: Option<PathBuf>,
+    /// Rows of data file loaded when it's picked or run.
+    #[serde(skip)]
+    data: Option<Result<Vec<Variables>, String>>,
+    #[serde(skip)]
+    pub show_runner_window: bool,
+    #[serde(skip)]
+    run: Option<Run>,
+}
+
+/// Request to send in a run.
+#[derive(Clone)]
+struct Step {
+    /// From 1.
+    iteration: u32,
+    id: String,
+    /// Index of data row whose variables are used.
+    row: Option<usize>,
+}
+
+struct Run {
+    steps: Vec<Step>,
+    /// Index of the next step to send.
+    next: usize,
+    sending: Option<Pending>,
+    /// The next step isn't sent before it.
+    wait_until: Instant,
+    /// Variables of environment and values extracted in this run.
+    variables: Variables,
+    /// Rows of data file,they override variables of environment.
+    rows: Vec<Variables>,
+    results: Vec<StepResult>,
+    started: Instant,
+    /// Time of the whole run when it's finished.
+    finished: Option<Duration>,
+    stopped: bool,
+}
+
+struct Pending {
+    step: Step,
+    path: String,
+    method: String,
+    assertions: Vec<Assertion>,
+    extractions: Vec<Extraction>,
+    variables: Variables,
+    sent: Instant,
+    sending: Sending,
+}
+
+struct StepResult {
+    step: Step,
+    path: String,
+    method: String,
+    status: Option<StatusCode>,
+    elapsed: Duration,
+    error: Option<String>,
+    outcomes: Vec<Outcome>,
+}
+
+impl Default for Runner {
+    fn default() -> Self {
+        Self {
+            selected: vec![],
+            iterations: 1,
+            delay: 0,
+            stop_on_failure: false,
+            data_file: None,
+            data: None,
+            show_runner_window: false,
+            run: None,
+        }
+    }
+}
+
+impl StepResult {
+    fn passed(&self) -> bool {
+        self.error.is_none() && self.outcomes.iter().all(|outcome| outcome.error.is_none())
+    }
+}
+
+impl Run {
+    fn new(steps: Vec<Step>, variables: Variables, rows: Vec<Variables>) -> Self {
+        Self {
+            steps,
+            next: 0,
+            sending: None,
+            wait_until: Instant::now(),
+            variables,
+            rows,
+            results: vec![],
+            started: Instant::now(),
+            finished: None,
+            stopped: false,
+        }
+    }
+
+    fn stop(&mut self) {
+        // Dropping request aborts it.
+        self.sending = None;
+        self.next = self.steps.len();
+        self.stopped = true;
+    }
+}
+
+impl Runner {
+    /// Open runner window with these requests selected.
+    pub fn open(&mut self, ids: Vec<String>) {
+        self.selected = ids;
+        self.show_runner_window = true;
+    }
+
+    fn is_running(&self) -> bool {
+        matches!(&self.run, Some(run) if run.finished.is_none())
+    }
+
+    /// Send the next request or check the sending one,it's called each frame even if window is closed.
+    /// Extracted values are put into `updates` of context.
+    fn poll(&mut self, ctx: &Context, collections: &Collections, context: &mut RequestContext) {
+        let Some(run) = &mut self.run else {
+            return;
+        };
+        if run.finished.is_some() {
+            return;
+        }
+        ctx.request_repaint();
+        if let Some(pending) = &run.sending {
+            let Some(result) = pending.sending.ready() else {
+                return;
+            };
+            let result = match result {
+                Ok(response) => {
+                    let outcomes =
+                        assertion::evaluate(&pending.assertions, response, &pending.variables);
+                    for extracted in
+                        extraction::extract(&pending.extractions, response, &pending.variables)
+                    {
+                        if let Ok(value) = extracted.value {
+                            run.variables
+                                .insert(extracted.variable.clone(), value.clone());
+                            context.updates.insert(extracted.variable, value);
+                        }
+                    }
+                    StepResult {
+                        step: pending.step.clone(),
+                        path: pending.path.clone(),
+                        method: pending.method.clone(),
+                        status: Some(response.code),
+                        elapsed: response.elapsed,
+                        error: None,
+                        outcomes,
+                    }
+                }
+                Err(err) => StepResult {
+                    step: pending.step.clone(),
+                    path: pending.path.clone(),
+                    method: pending.method.clone(),
+                    status: None,
+                    elapsed: pending.sent.elapsed(),
+                    error: Some(err.to_string()),
+                    outcomes: vec![],
+                },
+            };
+            let passed = result.passed();
+            run.results.push(result);
+            run.sending = None;
+            if self.stop_on_failure && !passed {
+                run.stop();
+            }
+            run.wait_until = Instant::now() + Duration::from_millis(self.delay);
+        }
+        if run.next >= run.steps.len() {
+            run.finished = Some(run.started.elapsed());
+            return;
+        }
+        if Instant::now() < run.wait_until {
+            return;
+        }
+        let step = run.steps[run.next].clone();
+        run.next += 1;
+        let requests = collections.requests();
+        match requests
+            .iter()
+            .find(|(_, http, _)| http.get_id() == step.id)
+        {
+            None => run.results.push(StepResult {
+                step,
+                path: "".to_string(),
+                method: "".to_string(),
+                status: None,
+                elapsed: Duration::ZERO,
+                error: Some("Request has been removed".to_string()),
+                outcomes: vec![],
+            }),
+            Some((path, http, defaults)) => {
+                let mut variables = run.variables.clone();
+                if let Some(row) = step.row.and_then(|row| run.rows.get(row)) {
+                    variables.extend(row.clone());
+                }
+                let sending = http.send(&RequestContext {
+                    variables: &variables,
+                    defaults,
+                    history: context.history,
+                    settings: context.settings,
+                    session: context.session,
+                    updates: context.updates,
+                });
+                run.sending = Some(Pending {
+                    step,
+                    path: path.clone(),
+                    method: http.method_name(),
+                    assertions: http.assertions().to_vec(),
+                    extractions: http.extractions().to_vec(),
+                    variables,
+                    sent: Instant::now(),
+                    sending,
+                });
+            }
+        }
+    }
+
+    /// Runner window,`context` gives variables of active environment and where requests are sent with.
+    pub fn draw_runner_window(
+        &mut self,
+        ctx: &Context,
+        collections: &Collections,
+        context: &mut RequestContext,
+    ) {
+        self.poll(ctx, collections, context);
+        let mut show = self.show_runner_window;
+        Window::new("Runner")
+            .resizable(true)
+            .open(&mut show)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                let running = self.is_running();
+                ui.add_enabled_ui(!running, |ui| self.options_view(ui, collections));
+                ui.add_space(5.0);
+                ui.horizontal(|ui| {
+                    if running {
+                        if ui.button("Stop").clicked() {
+                            if let Some(run) = &mut self.run {
+                                run.stop();
+                            }
+                        }
+                        return;
+                    }
+                    let ids: Vec<String> = collections
+                        .requests()
+                        .iter()
+                        .map(|(_, http, _)| http.get_id())
+                        .filter(|id| self.selected.contains(id))
+                        .collect();
+                    if ui
+                        .add_enabled(!ids.is_empty(), Button::new("Run"))
+                        .clicked()
+                    {
+                        // Data file is read again,it may have been changed.
+                        self.data = self.data_file.as_deref().map(load_data);
+                        let (rows, iterations) = match &self.data {
+                            None => (vec![], self.iterations as usize),
+                            Some(Ok(rows)) => (rows.clone(), rows.len()),
+                            Some(Err(_)) => (vec![], 0),
+                        };
+                        let with_rows = self.data.is_some();
+                        let steps: Vec<Step> = (0..iterations)
+                            .flat_map(|i| {
+                                ids.iter().map(move |id| Step {
+                                    iteration: i as u32 + 1,
+                                    id: id.clone(),
+                                    row: with_rows.then_some(i),
+                                })
+                            })
+                            .collect();
+                        if !steps.is_empty() {
+                            self.run = Some(Run::new(steps, context.variables.clone(), rows));
+                        }
+                    }
+                    let (failures, rows): (Vec<Step>, Vec<Variables>) = match &self.run {
+                        None => (vec![], vec![]),
+                        Some(run) => (
+                            run.results
+                                .iter()
+                                .filter(|result| !result.passed())
+                                .map(|result| result.step.clone())
+                                .collect(),
+                            run.rows.clone(),
+                        ),
+                    };
+                    if ui
+                        .add_enabled(!failures.is_empty(), Button::new("Re-run failures"))
+                        .clicked()
+                    {
+                        self.run = Some(Run::new(failures, context.variables.clone(), rows));
+                    }
+                });
+                if let Some(run) = &self.run {
+                    ui.add_space(5.0);
+                    run.summary_view(ui);
+                    ui.add_space(5.0);
+                    ScrollArea::vertical()
+                        .id_source("runner_results_scroll")
+                        .show(ui, |ui| run.results_view(ui));
+                }
+            });
+        self.show_runner_window = show;
+    }
+
+    fn options_view(&mut self, ui: &mut Ui, collections: &Collections) {
+        Grid::new("runner_options_grid")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Data file");
+                ui.horizontal(|ui| {
+                    let name = match &self.data_file {
+                        None => "Pick CSV or JSON…".to_string(),
+                        Some(path) => path.display().to_string(),
+                    };
+                    if ui.button(name).clicked() {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .add_filter("CSV or JSON", &["csv", "json"])
+                            .pick_file()
+                        {
+                            self.data = Some(load_data(&path));
+                            self.data_file = Some(path);
+                        }
+                    }
+                    if self.data_file.is_some() && ui.button("🗑").clicked() {
+                        self.data_file = None;
+                        self.data = None;
+                    }
+                    match &self.data {
+                        Some(Ok(rows)) => {
+                            ui.colored_label(color::GRAY, format!("{} rows", rows.len()))
+                        }
+                        Some(Err(err)) => ui.colored_label(color::CRIMSON, err),
+                        None => ui.label(""),
+                    };
+                });
+                ui.end_row();
+                ui.label("Iterations");
+                ui.add_enabled(
+                    self.data_file.is_none(),
+                    DragValue::new(&mut self.iterations).clamp_range(1..=1000),
+                )
+                .on_disabled_hover_text("Once per row of data file");
+                ui.end_row();
+                ui.label("Delay");
+                ui.add(DragValue::new(&mut self.delay).suffix(" ms"));
+                ui.end_row();
+                ui.label("");
+                ui.checkbox(&mut self.stop_on_failure, "Stop on failure");
+                ui.end_row();
+            });
+        ui.add_space(5.0);
+        let requests = collections.requests();
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "Requests ({}/{})",
+                requests
+                    .iter()
+                    .filter(|(_, http, _)| self.selected.contains(&http.get_id()))
+                    .count(),
+                requests.len()
+            ));
+            ComboBox::from_id_source("runner_folder_combo_box")
+                .selected_text("Select folder")
+                .show_ui(ui, |ui| {
+                    for (_, path, ids) in collections.folders() {
+                        if ui.selectable_label(false, path).clicked() {
+                            self.selected = ids;
+                        }
+                    }
+                });
+            if ui.button("All").clicked() {
+                self.selected = requests.iter().map(|(_, http, _)| http.get_id()).collect();
+            }
+            if ui.button("None").clicked() {
+                self.selected.clear();
+            }
+        });
+        ScrollArea::vertical()
+            .id_source("runner_requests_scroll")
+            .max_height(200.0)
+            .show(ui, |ui| {
+                for (path, http, _) in &requests {
+                    let id = http.get_id();
+                    let mut checked = self.selected.contains(&id);
+                    let text = format!("{} {}", http.method_name(), path);
+                    if ui.add(Checkbox::new(&mut checked, text)).changed() {
+                        match checked {
+                            true => self.selected.push(id),
+                            false => self.selected.retain(|selected| *selected != id),
+                        }
+                    }
+                }
+            });
+    }
+}
+
+impl Run {
+    fn summary_view(&self, ui: &mut Ui) {
+        let passed = self.results.iter().filter(|result| result.passed()).count();
+        let failed = self.results.len() - passed;
+        let done = self.results.len() as f32 / self.steps.len().max(1) as f32;
+        let time = self.finished.unwrap_or_else(|| self.started.elapsed());
+        let state = match (self.finished, self.stopped) {
+            (None, _) => "Running",
+            (Some(_), true) => "Stopped",
+            (Some(_), false) => "Finished",
+        };
+        ui.add(ProgressBar::new(done).text(format!(
+            "{} {}/{}",
+            state,
+            self.results.len(),
+            self.steps.len()
+        )));
+        ui.horizontal(|ui| {
+            ui.colored_label(color::SEA_GREEN, format!("Passed: {}", passed));
+            ui.colored_label(
+                match failed {
+                    0 => color::GRAY,
+                    _ => color::CRIMSON,
+                },
+                format!("Failed: {}", failed),
+            );
+            ui.label(format!("Time: {}", format_duration(time)));
+        });
+        let mut failed_rows: Vec<usize> = self
+            .results
+            .iter()
+            .filter(|result| !result.passed())
+            .filter_map(|result| result.step.row)
+            .collect();
+        failed_rows.dedup();
+        if !failed_rows.is_empty() {
+            ui.colored_label(
+                color::CRIMSON,
+                format!(
+                    "Failed rows: {}",
+                    failed_rows
+                        .iter()
+                        .map(|row| (row + 1).to_string())
+                        .collect::<Vec<String>>()
+                        .join(",")
+                ),
+            );
+        }
+    }
+
+    /// Number of iteration,or data row with its variables on hover.
+    fn iteration_view(&self, ui: &mut Ui, step: &Step) {
+        match step.row.and_then(|row| self.rows.get(row)) {
+            None => ui.label(step.iteration.to_string()),
+            Some(row) => {
+                let mut variables: Vec<String> = row
+                    .iter()
+                    .map(|(key, value)| format!("{} = {}", key, value))
+                    .collect();
+                variables.sort();
+                ui.label(format!("Row {}", step.iteration))
+                    .on_hover_text(variables.join("\n"))
+            }
+        };
+    }
+
+    fn results_view(&self, ui: &mut Ui) {
+        Grid::new("runner_results_grid")
+            .num_columns(5)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("#");
+                ui.label("Request");
+                ui.label("Status");
+                ui.label("Time");
+                ui.label("Tests");
+                ui.end_row();
+                for result in &self.results {
+                    self.iteration_view(ui, &result.step);
+                    ui.label(format!("{} {}", result.method, result.path));
+                    match result.status {
+                        Some(status) => ui.colored_label(
+                            match status.is_success() {
+                                true => color::SEA_GREEN,
+                                false => color::CRIMSON,
+                            },
+                            status.as_str(),
+                        ),
+                        None => ui.colored_label(color::CRIMSON, "Error"),
+                    };
+                    ui.label(format_duration(result.elapsed));
+                    ui.vertical(|ui| {
+                        if let Some(err) = &result.error {
+                            ui.colored_label(color::CRIMSON, err);
+                        }
+                        for outcome in &result.outcomes {
+                            if let Some(err) = &outcome.error {
+                                ui.colored_label(
+                                    color::CRIMSON,
+                                    format!("✘ {}: {}", outcome.name, err),
+                                );
+                            }
+                        }
+                        if result.passed() {
+                            match result.outcomes.len() {
+                                0 => ui.label("-"),
+                                len => {
+                                    ui.colored_label(color::SEA_GREEN, format!("✔ {} passed", len))
+                                }
+                            };
+                        }
+                    });
+                    ui.end_row();
+                }
+                if let Some(pending) = &self.sending {
+                    self.iteration_view(ui, &pending.step);
+                    ui.label(format!("{} {}", pending.method, pending.path));
+                    ui.colored_label(color::GRAY, "Sending");
+                    ui.label(format_duration(pending.sent.elapsed()));
+                    ui.label("");
+                    ui.end_row();
+                }
+            });
+    }
+}
+
+/// Rows of CSV file with header,or JSON array of objects.
+fn load_data(path: &Path) -> Result<Vec<Variables>, String> {
+    let text =
+        std::fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+    let is_json = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+    match is_json {
+        true => parse_json(&text),
+        false => parse_csv(&text),
+    }
+}
+
+fn parse_json(text: &str) -> Result<Vec<Variables>, String> {
+    let value: Value = serde_json::from_str(text).map_err(|err| err.to_string())?;
+    let rows = value
+        .as_array()
+        .ok_or("Data should be an array of objects")?;
+    rows.iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let row = row
+                .as_object()
+                .ok_or_else(|| format!("Row {} isn't an object", i + 1))?;
+            Ok(row
+                .iter()
+                .map(|(key, value)| {
+                    let value = match value {
+                        Value::String(text) => text.clone(),
+                        Value::Null => "".to_string(),
+                        value => value.to_string(),
+                    };
+                    (key.clone(), value)
+                })
+                .collect())
+        })
+        .collect()
+}
+
+/// CSV with header,fields may be quoted with `"` and `""` is an escaped quote.
+fn parse_csv(text: &str) -> Result<Vec<Variables>, String> {
+    let mut records: Vec<Vec<String>> = vec![];
+    let mut record = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted => match chars.peek() {
+                Some('"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                _ => quoted = false,
+            },
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => record.push(std::mem::take(&mut field)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return Err("Quote isn't closed".to_string());
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    // Blank lines are skipped.
+    records.retain(|record| record.len() > 1 || !record[0].trim().is_empty());
+    let mut records = records.into_iter();
+    let header = records.next().ok_or("Data file is empty")?;
+    records
+        .enumerate()
+        .map(|(i, record)| match record.len() == header.len() {
+            true => Ok(header
+                .iter()
+                .map(|key| key.trim().to_string())
+                .zip(record)
+                .collect()),
+            false => Err(format!(
+                "Row {} has {} fields,header has {}",
+                i + 1,
+                record.len(),
+                header.len()
+            )),
+        })
+        .collect()
+}
+
+#[test]
+fn test_parse_data() {
+    let rows = parse_csv(
+        "\u{feff}user, password\r\nalice,\"p,w\"\r\n\nbob,\"say \"\"hi\"\"\"\n\"multi\nline\",\n",
+    )
+    .unwrap();
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0]["user"], "alice");
+    assert_eq!(rows[0]["password"], "p,w");
+    assert_eq!(rows[1]["password"], "say \"hi\"");
+    assert_eq!(rows[2]["user"], "multi\nline");
+    assert_eq!(rows[2]["password"], "");
+    assert_eq!(
+        parse_csv("a,b\n1\n").unwrap_err(),
+        "Row 1 has 1 fields,header has 2"
+    );
+    assert!(parse_csv("a\n\"1\n").is_err());
+
+    let rows = parse_json(r#"[{"id":1,"name":"x","tags":["a"],"none":null}]"#).unwrap();
+    assert_eq!(rows[0]["id"], "1");
+    assert_eq!(rows[0]["name"], "x");
+    assert_eq!(rows[0]["tags"], "[\"a\"]");
+    assert_eq!(rows[0]["none"], "");
+    assert!(parse_json(r#"{"id":1}"#).is_err());
+}
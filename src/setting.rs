@@ -1,8 +1,11 @@
+use crate::request::proxy::Proxy;
+use crate::request::tls::Tls;
 use eframe::egui;
 use eframe::egui::style::TextStyle::{Body, Button, Heading, Monospace, Small};
 use eframe::egui::FontFamily::Proportional;
 use eframe::egui::{
-    ComboBox, Context, DragValue, FontData, FontDefinitions, FontFamily, FontId, Grid, Window,
+    CollapsingHeader, ComboBox, Context, DragValue, FontData, FontDefinitions, FontFamily, FontId,
+    Grid, Ui, Window,
 };
 use font_kit::family_name::FamilyName;
 use font_kit::handle::Handle;
@@ -10,6 +13,7 @@ use font_kit::properties::Properties;
 use font_kit::source::SystemSource;
 use serde::{Deserialize, Serialize};
 use std::ops::RangeInclusive;
+use std::time::Duration;
 
 #[derive(Deserialize, Serialize, Clone)]
 pub struct Settings {
@@ -19,6 +23,62 @@ pub struct Settings {
     pub font: String,
     #[serde(skip)]
     pub system_font: Vec<String>,
+    /// Used by requests which don't override it.
+    #[serde(default)]
+    pub timeouts: Timeouts,
+    /// Used by requests which don't override it.
+    #[serde(default)]
+    pub proxy: Proxy,
+    /// Used by all requests,those of request are added to it.
+    #[serde(default)]
+    pub tls: Tls,
+}
+
+/// Timeouts in seconds,`0` means no limit.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Timeouts {
+    pub connect: u64,
+    /// From sending request to receiving the whole body.
+    pub total: u64,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            connect: 30,
+            total: 300,
+        }
+    }
+}
+
+impl Timeouts {
+    pub fn connect(&self) -> Option<Duration> {
+        (self.connect > 0).then(|| Duration::from_secs(self.connect))
+    }
+
+    pub fn total(&self) -> Option<Duration> {
+        (self.total > 0).then(|| Duration::from_secs(self.total))
+    }
+
+    /// Rows of grid with two columns.
+    pub fn grid_view(&mut self, ui: &mut Ui) {
+        ui.label("Connect timeout");
+        ui.add(
+            DragValue::new(&mut self.connect)
+                .clamp_range(RangeInclusive::new(0, 3600))
+                .suffix(" s"),
+        )
+        .on_hover_text("0 means no limit");
+        ui.end_row();
+        ui.label("Total timeout");
+        ui.add(
+            DragValue::new(&mut self.total)
+                .clamp_range(RangeInclusive::new(0, 3600))
+                .suffix(" s"),
+        )
+        .on_hover_text("0 means no limit");
+        ui.end_row();
+    }
 }
 
 impl Default for Settings {
@@ -28,6 +88,9 @@ impl Default for Settings {
             font_size: 20.0,
             font: "".to_string(),
             system_font: vec![],
+            timeouts: Default::default(),
+            proxy: Default::default(),
+            tls: Default::default(),
         }
     }
 }
@@ -75,7 +138,10 @@ impl Settings {
                                 }
                             });
                         ui.end_row();
+                        self.timeouts.grid_view(ui);
+                        self.proxy.grid_view(ui);
                     });
+                CollapsingHeader::new("TLS").show(ui, |ui| self.tls.view(ui, "settings_tls"));
             });
     }
 
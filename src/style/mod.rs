@@ -19,7 +19,7 @@ impl WeaverStyle {
     }
 }
 
-fn load_image_from_memory(image_data: &[u8]) -> Result<ColorImage, image::ImageError> {
+pub fn load_image_from_memory(image_data: &[u8]) -> Result<ColorImage, image::ImageError> {
     let image = image::load_from_memory(image_data)?;
     let size = [image.width() as _, image.height() as _];
     let image_buffer = image.to_rgba8();